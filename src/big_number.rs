@@ -0,0 +1,271 @@
+use crate::{Error, RespDataKind, Result};
+use serde::de::Visitor;
+
+/// A RESP [Big Number](https://redis.io/docs/latest/develop/reference/protocol-spec/#big-numbers)
+/// of any size, kept as its string of digits (with an optional sign).
+///
+/// Unlike the Rust integer types, it holds any value a server may send, and writes it back
+/// exactly as it was read. It is also deserializable from RESP integers and strings of digits.
+///
+/// ```
+/// # use rediserde::{BigNumber, from_str, to_string};
+/// let raw = "(3492890328409238509324850943850943825024385\r\n";
+/// let number: BigNumber = from_str(raw).unwrap();
+/// assert_eq!(number.as_str(), "3492890328409238509324850943850943825024385");
+/// assert_eq!(to_string(&number).unwrap(), raw);
+///
+/// assert_eq!(BigNumber::from(-5_i64).as_str(), "-5");
+/// assert!(BigNumber::new("12a").is_err());
+/// ```
+///
+/// With the `num-bigint` feature, it converts to and from `num_bigint::BigInt` and `BigUint`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigNumber(String);
+
+impl BigNumber {
+    /// Checks that `number` is an optional sign (`+` or `-`) followed by at least one digit.
+    pub fn new(number: impl Into<String>) -> Result<Self> {
+        let number = number.into();
+        check_digits(&number)?;
+        Ok(Self(number))
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Whether the number is below zero (`-0` is not).
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        self.0
+            .strip_prefix('-')
+            .is_some_and(|digits| digits.bytes().any(|b| b != b'0'))
+    }
+}
+
+/// Checks the data of a big number without copying it, see [`BigNumber::new`].
+pub(crate) fn check_digits(number: &str) -> Result<()> {
+    let digits = number.strip_prefix(['+', '-']).unwrap_or(number);
+    if let Some(found) = digits.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(Error::UnexpectedByte {
+            expected: "A digit".to_string(),
+            found,
+        });
+    }
+    if digits.is_empty() {
+        return Err(Error::DeserializeError(
+            "A big number needs at least one digit".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl std::str::FromStr for BigNumber {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for BigNumber {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for BigNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigNumber {
+                fn from(value: $t) -> Self {
+                    Self(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl serde::Serialize for BigNumber {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct(RespDataKind::BigNumber.to_token(), &self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for BigNumber {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(BigNumberVisitor)
+    }
+}
+
+struct BigNumberVisitor;
+
+impl Visitor<'_> for BigNumberVisitor {
+    type Value = BigNumber;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a big number")
+    }
+
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i128<E>(self, v: i128) -> std::result::Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u128<E>(self, v: u128) -> std::result::Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        BigNumber::new(v).map_err(E::custom)
+    }
+
+    fn visit_string<E>(self, v: String) -> std::result::Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        BigNumber::new(v).map_err(E::custom)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl From<num_bigint::BigInt> for BigNumber {
+    fn from(value: num_bigint::BigInt) -> Self {
+        Self(value.to_string())
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl From<num_bigint::BigUint> for BigNumber {
+    fn from(value: num_bigint::BigUint) -> Self {
+        Self(value.to_string())
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl From<&BigNumber> for num_bigint::BigInt {
+    fn from(value: &BigNumber) -> Self {
+        value
+            .0
+            .parse()
+            .expect("A big number is always a valid BigInt")
+    }
+}
+
+/// Fails for negative numbers.
+#[cfg(feature = "num-bigint")]
+impl TryFrom<&BigNumber> for num_bigint::BigUint {
+    type Error = Error;
+
+    fn try_from(value: &BigNumber) -> Result<Self> {
+        Self::try_from(num_bigint::BigInt::from(value)).map_err(|_| Error::UnexpectedByte {
+            expected: "A non-negative number".to_string(),
+            found: '-',
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Value, from_str, from_value, to_string, to_value};
+
+    #[test]
+    fn test_new() {
+        for valid in [
+            "0",
+            "-0",
+            "+12",
+            "-12",
+            "007",
+            &u128::MAX.to_string().repeat(3),
+        ] {
+            assert_eq!(BigNumber::new(valid).unwrap().as_str(), valid);
+        }
+        for invalid in ["", "-", "+-1", "1.5", "1e5", " 1", "1_000", "١"] {
+            assert!(BigNumber::new(invalid).is_err(), "{invalid}");
+        }
+        assert!(BigNumber::new("-1").unwrap().is_negative());
+        assert!(!BigNumber::new("-00").unwrap().is_negative());
+        assert!(!BigNumber::from(u128::MAX).is_negative());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for raw in [
+            "(+007\r\n",
+            "(-0\r\n",
+            "(123456789012345678901234567890123456789012\r\n",
+        ] {
+            let number: BigNumber = from_str(raw).unwrap();
+            assert_eq!(to_string(&number).unwrap(), raw);
+            let value = to_value(&number).unwrap();
+            assert_eq!(value, Value::BigNumber(number.clone()));
+            assert_eq!(from_value::<BigNumber>(value).unwrap(), number);
+        }
+        let number: BigNumber = from_str(":-5\r\n").unwrap();
+        assert_eq!(number.as_str(), "-5");
+        let number: BigNumber = from_str("$3\r\n123\r\n").unwrap();
+        assert_eq!(number.as_str(), "123");
+        assert!(from_str::<BigNumber>("(12a\r\n").is_err());
+        assert!(from_str::<BigNumber>(",1.5\r\n").is_err());
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_num_bigint() {
+        use num_bigint::{BigInt, BigUint};
+
+        let big: BigInt = BigInt::from(u128::MAX) * 1000 + 7;
+        let number = BigNumber::from(-big.clone());
+        assert_eq!(
+            number.as_str(),
+            "-340282366920938463463374607431768211455007"
+        );
+        assert_eq!(BigInt::from(&number), -big.clone());
+        assert!(BigUint::try_from(&number).is_err());
+        let number: BigNumber =
+            from_str("(+340282366920938463463374607431768211455007\r\n").unwrap();
+        assert_eq!(
+            BigUint::try_from(&number).unwrap(),
+            big.to_biguint().unwrap()
+        );
+        assert_eq!(
+            BigUint::try_from(&BigNumber::new("-0").unwrap()).unwrap(),
+            BigUint::ZERO
+        );
+        assert_eq!(BigNumber::from(BigUint::from(5_u8)).as_str(), "5");
+    }
+}
@@ -0,0 +1,231 @@
+//! A [`tokio_util::codec`] decoder and encoder, for framed transports like `Framed<TcpStream, _>`.
+
+use crate::{Decoded, Error, Result};
+use bytes::BytesMut;
+use serde::{Serialize, de::DeserializeOwned};
+use std::marker::PhantomData;
+
+/// The most that's reserved up front for a value that's still arriving. The declared length
+/// of a bulk value comes from the peer, so reserving all of it would let it allocate anything.
+const MAX_RESERVE: usize = 8 * 1024;
+
+/// The default maximum frame length, the same as the largest bulk string Redis accepts.
+const DEFAULT_MAX_LENGTH: usize = 512 * 1024 * 1024;
+
+/// Decodes RESP values of type `T`, and encodes any serializable value.
+///
+/// Partially received values are not scanned again when more bytes arrive (see [`crate::Decoder`]).
+/// Fields using [`crate::shared_bytes`] share the allocation of the received data.
+///
+/// A value longer than the maximum frame length (512 MiB unless set with
+/// [`RespCodec::new_with_max_length`]) fails with [`Error::FrameTooLong`] as soon as that's
+/// known, so a peer can't make the buffer grow without bounds.
+///
+/// ```
+/// # use rediserde::{RespCodec, Value};
+/// # use bytes::BytesMut;
+/// # use tokio_util::codec::{Decoder, Encoder};
+/// let mut codec = RespCodec::<Value>::new();
+/// let mut buffer = BytesMut::new();
+/// codec.encode(["PING"], &mut buffer).unwrap();
+/// assert_eq!(&buffer[..], b"*1\r\n$4\r\nPING\r\n");
+///
+/// let mut buffer = BytesMut::from(&b"+PO"[..]);
+/// assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+/// buffer.extend_from_slice(b"NG\r\n");
+/// assert_eq!(codec.decode(&mut buffer).unwrap(), Some(Value::SimpleString("PONG".to_string())));
+/// assert!(buffer.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RespCodec<T> {
+    decoder: crate::Decoder,
+    max_length: usize,
+    output: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for RespCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RespCodec<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::new_with_max_length(DEFAULT_MAX_LENGTH)
+    }
+
+    /// A codec that fails on values longer than `max_length` bytes, including their headers.
+    #[must_use]
+    pub const fn new_with_max_length(max_length: usize) -> Self {
+        Self {
+            decoder: crate::Decoder::new(),
+            max_length,
+            output: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub const fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+impl<T> tokio_util::codec::Decoder for RespCodec<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>> {
+        match self.decoder.decode_len(src)? {
+            Decoded::Complete((), consumed) if consumed > self.max_length => {
+                Err(Error::FrameTooLong)
+            }
+            Decoded::Complete((), consumed) => {
+                let frame = src.split_to(consumed).freeze();
+                crate::from_shared_bytes(&frame).map(Some)
+            }
+            Decoded::Incomplete { needed } => {
+                let needed = needed.unwrap_or(0);
+                if src.len().saturating_add(needed) > self.max_length {
+                    self.decoder = crate::Decoder::new();
+                    return Err(Error::FrameTooLong);
+                }
+                src.reserve(needed.min(MAX_RESERVE));
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<T, I> tokio_util::codec::Encoder<I> for RespCodec<T>
+where
+    I: Serialize,
+{
+    type Error = Error;
+
+    fn encode(&mut self, item: I, dst: &mut BytesMut) -> Result<()> {
+        crate::to_bytes_mut(dst, &item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::io::AsyncWriteExt;
+    use tokio_util::codec::{Framed, FramedRead};
+
+    #[tokio::test]
+    async fn test_framed() {
+        let (client, server) = tokio::io::duplex(64);
+        let mut client = Framed::new(client, RespCodec::<Value>::new());
+        let mut server = Framed::new(server, RespCodec::<Vec<String>>::new());
+
+        client.send(["SET", "key", "value"]).await.unwrap();
+        client.send(["GET", "key"]).await.unwrap();
+        let command = server.next().await.unwrap().unwrap();
+        assert_eq!(command, ["SET", "key", "value"]);
+        let command = server.next().await.unwrap().unwrap();
+        assert_eq!(command, ["GET", "key"]);
+
+        // Larger than the pipe, so sending and receiving must happen together
+        let reply = Value::BulkString(vec![b'x'; 1000]);
+        let (sent, received) = tokio::join!(server.send(&reply), client.next());
+        sent.unwrap();
+        assert_eq!(received.unwrap().unwrap(), reply);
+    }
+
+    #[tokio::test]
+    async fn test_partial() {
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let mut reader = FramedRead::new(reader, RespCodec::<Vec<u8>>::new());
+        let raw = b"*3\r\n:1\r\n:2\r\n:3\r\n*0\r\n";
+        let write = async move {
+            for byte in raw {
+                writer.write_all(&[*byte]).await.unwrap();
+                tokio::task::yield_now().await;
+            }
+        };
+        let read = async {
+            let first = reader.next().await.unwrap().unwrap();
+            let second = reader.next().await.unwrap().unwrap();
+            (first, second, reader.next().await)
+        };
+        let ((), (first, second, end)) = tokio::join!(write, read);
+        assert_eq!(first, [1, 2, 3]);
+        assert!(second.is_empty());
+        assert!(end.is_none(), "The writer was dropped");
+
+        let mut codec = RespCodec::<Value>::new();
+        let mut buffer = BytesMut::from(&b"$100000000\r\nab"[..]);
+        assert_eq!(
+            tokio_util::codec::Decoder::decode(&mut codec, &mut buffer).unwrap(),
+            None
+        );
+        assert!(buffer.capacity() <= buffer.len() + MAX_RESERVE);
+    }
+
+    #[test]
+    fn test_max_length() {
+        use tokio_util::codec::Decoder;
+
+        let mut codec = RespCodec::<Value>::new();
+        assert_eq!(codec.max_length(), DEFAULT_MAX_LENGTH);
+        let mut buffer = BytesMut::from(&b"$9000000000000000000\r\n"[..]);
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(Error::FrameTooLong)
+        ));
+
+        let mut codec = RespCodec::<Value>::new_with_max_length(16);
+        let mut buffer = BytesMut::from(&b"+0123456789abc\r\n$11\r\n"[..]);
+        assert!(
+            codec.decode(&mut buffer).unwrap().is_some(),
+            "Exactly 16 bytes"
+        );
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(Error::FrameTooLong)
+        ));
+
+        let mut codec = RespCodec::<Value>::new_with_max_length(16);
+        let mut buffer = BytesMut::from(&b"*?\r\n"[..]);
+        let error = loop {
+            buffer.extend_from_slice(b":1\r\n");
+            match codec.decode(&mut buffer) {
+                Ok(decoded) => assert_eq!(decoded, None),
+                Err(err) => break err,
+            }
+        };
+        assert!(matches!(error, Error::FrameTooLong));
+        assert!(buffer.len() <= 20);
+
+        let mut buffer = BytesMut::from(&b"*4\r\n+a\r\n+b\r\n+c\r\n+d\r\n"[..]);
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(Error::FrameTooLong)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let mut reader = FramedRead::new(reader, RespCodec::<u8>::new());
+        writer.write_all(b":300\r\n:1").await.unwrap();
+        drop(writer);
+        assert!(reader.next().await.unwrap().is_err());
+
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let mut reader = FramedRead::new(reader, RespCodec::<u8>::new());
+        writer.write_all(b":1").await.unwrap();
+        drop(writer);
+        assert!(
+            reader.next().await.unwrap().is_err(),
+            "Ends in the middle of a value"
+        );
+    }
+}
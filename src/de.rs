@@ -1,18 +1,225 @@
 #![allow(clippy::missing_errors_doc)]
 
-use crate::{CRLF, CRLF_STR, Error, RespDataKind, Result};
+use crate::{CRLF, CRLF_STR, Error, RespDataKind, Result, STREAMED, STREAMED_CHUNK, STREAMED_END};
 use serde::de::IntoDeserializer;
+use std::borrow::Cow;
+
+/// The start of a value, see `Deserializer::next_header`.
+pub(crate) enum Header<'de> {
+    /// A complete non-aggregate value and its data, excluding the prefix, length and CRLFs.
+    /// Nulls (including RESP2 nulls) have no data. The chunks of streamed strings are joined,
+    /// which is the only case where the data is not borrowed.
+    Scalar(RespDataKind, Cow<'de, [u8]>),
+    /// The header of an aggregate with the length it declares, followed by its values.
+    /// Streamed aggregates have no length, and their values are followed by [`Header::End`].
+    Aggregate(RespDataKind, Option<usize>),
+    /// The end of a streamed aggregate.
+    End,
+}
+
+/// The error for a streamed map (or attributes) that ends with a key but no value.
+pub(crate) fn streamed_map_error() -> Error {
+    Error::DeserializeError("A streamed map ended between a key and its value".to_string())
+}
+
+/// Counts the values still missing to complete a frame, from the headers read so far.
+///
+/// Only allocates for streamed maps, and for streamed aggregates nested in aggregates of
+/// known length.
+#[derive(Debug, Clone)]
+pub(crate) struct Pending {
+    /// The values missing from aggregates of known length, including nested ones.
+    count: usize,
+    /// The number of open streamed aggregates.
+    streamed: usize,
+    /// The `count` outside of open streamed aggregates where it wasn't 0,
+    /// with the number of streamed aggregates that were open around them.
+    outer: Vec<(usize, usize)>,
+    /// The open streamed maps (and attributes), as the number of streamed aggregates open
+    /// around their values, and whether a key is still waiting for its value.
+    maps: Vec<(usize, bool)>,
+}
+
+impl Pending {
+    /// Expects a single value.
+    pub(crate) const fn new() -> Self {
+        Self {
+            count: 1,
+            streamed: 0,
+            outer: Vec::new(),
+            maps: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.count == 0 && self.streamed == 0
+    }
+
+    /// Counts the next header, failing if it's the end of a streamed aggregate in the wrong place.
+    pub(crate) fn push(&mut self, header: &Header) -> Result<()> {
+        if let Header::End = header {
+            if self.count > 0 || self.streamed == 0 {
+                return Err(Error::UnrecognizedStart);
+            }
+            if let Some(&(streamed, key)) = self.maps.last()
+                && streamed == self.streamed
+            {
+                if key {
+                    return Err(streamed_map_error());
+                }
+                self.maps.pop();
+            }
+            self.streamed -= 1;
+            if let Some(&(streamed, count)) = self.outer.last()
+                && streamed == self.streamed
+            {
+                self.outer.pop();
+                self.count = count;
+            }
+            return Ok(());
+        }
+        // Directly inside a streamed aggregate the count is 0, any number of values may follow
+        if self.count == 0
+            && let Some((streamed, key)) = self.maps.last_mut()
+            && *streamed == self.streamed
+        {
+            *key = !*key;
+        }
+        self.count = self.count.saturating_sub(1);
+        match header {
+            Header::Aggregate(kind, Some(length)) => {
+                self.count = self
+                    .count
+                    .checked_add(kind.children(*length)?)
+                    .ok_or(Error::ExpectedLength)?;
+            }
+            Header::Aggregate(kind, None) => {
+                if self.count > 0 {
+                    self.outer.push((self.streamed, self.count));
+                }
+                self.streamed += 1;
+                self.count = 0;
+                if matches!(kind, RespDataKind::Map | RespDataKind::Attributes) {
+                    self.maps.push((self.streamed, false));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
 
-const VALID_NUMERIC_CHARS: &[u8] = b"0123456789+-.eE";
+/// How many aggregates may be nested in each other, the same limit as `serde_json`.
+const RECURSION_LIMIT: u8 = 128;
 
+/// Deserializes RESP data from a slice.
+///
+/// Aggregates may be nested at most 128 levels deep, deeper input fails with
+/// [`Error::RecursionLimitExceeded`] instead of overflowing the stack.
 pub struct Deserializer<'de> {
     input: &'de [u8],
+    remaining_depth: u8,
 }
 
 impl<'de> Deserializer<'de> {
     #[must_use]
     pub const fn new(input: &'de [u8]) -> Self {
-        Self { input }
+        Self {
+            input,
+            remaining_depth: RECURSION_LIMIT,
+        }
+    }
+
+    /// Runs `f` on the contents of an aggregate, one nesting level deeper.
+    pub(crate) fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.remaining_depth = self
+            .remaining_depth
+            .checked_sub(1)
+            .ok_or(Error::RecursionLimitExceeded)?;
+        let result = f(self);
+        self.remaining_depth += 1;
+        result
+    }
+
+    /// Turns the deserializer into an iterator over all the values in the input, one after
+    /// the other, as in pipelined responses or AOF files.
+    ///
+    /// Each value comes with the byte offset where it started. Iteration stops after the
+    /// first error, including a value that was cut short at the end of the input.
+    ///
+    /// ```
+    /// # use rediserde::{Deserializer, Value};
+    /// let input = b"+OK\r\n:1\r\n:2\r\n";
+    /// let values: Vec<(usize, Value)> = Deserializer::new(input)
+    ///     .into_iter()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(values, vec![
+    ///     (0, Value::SimpleString("OK".to_string())),
+    ///     (5, Value::Integer(1)),
+    ///     (9, Value::Integer(2)),
+    /// ]);
+    /// ```
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
+    pub fn into_iter<T>(self) -> StreamDeserializer<'de, T>
+    where
+        T: serde::de::Deserialize<'de>,
+    {
+        StreamDeserializer {
+            length: self.input.len(),
+            de: self,
+            failed: false,
+            output: std::marker::PhantomData,
+        }
+    }
+
+    /// Reads the header of an array (or set, or push) and returns an iterator over its elements,
+    /// deserializing each one only when it's reached instead of collecting all of them up front.
+    ///
+    /// Once the iterator is exhausted, the deserializer is right after the array. Iteration
+    /// stops after the first error. A null array has no elements.
+    ///
+    /// ```
+    /// # use rediserde::Deserializer;
+    /// let mut deserializer = Deserializer::new(b"~3\r\n+a\r\n+b\r\n+c\r\n");
+    /// let mut members = deserializer.iter_array::<String>().unwrap();
+    /// assert_eq!(members.size_hint(), (3, Some(3)));
+    /// assert_eq!(members.next().unwrap().unwrap(), "a");
+    /// assert_eq!(members.count(), 2);
+    /// ```
+    pub fn iter_array<T>(&mut self) -> Result<ArrayIter<'_, 'de, T>>
+    where
+        T: serde::de::Deserialize<'de>,
+    {
+        let remaining = match self.next_header()? {
+            Header::Aggregate(
+                RespDataKind::Array | RespDataKind::Set | RespDataKind::Push,
+                length,
+            ) => length,
+            Header::Scalar(RespDataKind::Null, _) => Some(0),
+            _ => {
+                return Err(Error::DeserializeError(
+                    "Expected an array, set, or push".to_string(),
+                ));
+            }
+        };
+        Ok(ArrayIter {
+            de: self,
+            remaining,
+            failed: false,
+            output: std::marker::PhantomData,
+        })
+    }
+
+    /// Whether all of the input was consumed.
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    /// The number of input bytes not consumed yet.
+    pub(crate) const fn remaining(&self) -> usize {
+        self.input.len()
     }
 
     fn next_byte(&mut self) -> Result<u8> {
@@ -49,7 +256,7 @@ impl<'de> Deserializer<'de> {
         if self.input.starts_with(CRLF) {
             self.input = &self.input[CRLF.len()..];
             Ok(())
-        } else if self.input.is_empty() {
+        } else if CRLF.starts_with(self.input) {
             Err(Error::UnexpectedEnd)
         } else {
             Err(Error::UnexpectedByte {
@@ -61,13 +268,16 @@ impl<'de> Deserializer<'de> {
 
     /// Expects and consumes a numeric value
     fn expect_length(&mut self) -> Result<usize> {
+        if self.input.len() < 4 && b"-1\r\n".starts_with(self.input) {
+            return Err(Error::UnexpectedEnd); // Possibly a RESP2 null that was cut short
+        }
         let first_non_numeric = self
             .input
             .iter()
             .position(|&b| !b.is_ascii_digit())
-            .ok_or(Error::ExpectedLength)?;
-        let length_str = str::from_utf8(&self.input[..first_non_numeric])
-            .map_err(|_| Error::ExpectedLength)?;
+            .ok_or(Error::UnexpectedEnd)?;
+        let length_str =
+            str::from_utf8(&self.input[..first_non_numeric]).map_err(|_| Error::ExpectedLength)?;
         self.input = &self.input[first_non_numeric..];
         let length = length_str
             .parse::<usize>()
@@ -75,27 +285,38 @@ impl<'de> Deserializer<'de> {
         Ok(length)
     }
 
-    fn parse_string(&mut self) -> Result<&str> {
-        let first = self.next_byte()?;
-        let kind = RespDataKind::try_from(first).map_err(|()| Error::UnrecognizedStart)?;
-        let result = match kind {
-            RespDataKind::SimpleString
-            | RespDataKind::SimpleError
-            | RespDataKind::Integer
-            | RespDataKind::BigNumber
-            | RespDataKind::Float => self.parse_simple_string(),
-            RespDataKind::BulkString | RespDataKind::BulkError | RespDataKind::VerbatimString => {
-                self.parse_bulk_string()
+    /// Expects and consumes the length of an aggregate, `None` for streamed aggregates.
+    fn expect_aggregate_length(&mut self) -> Result<Option<usize>> {
+        if self.input.first() == Some(&STREAMED) {
+            self.next_byte()?;
+            Ok(None)
+        } else {
+            self.expect_length().map(Some)
+        }
+    }
+
+    /// Consumes the end of a streamed aggregate if it's next, `.\r\n`.
+    fn parse_streamed_end(&mut self) -> Result<bool> {
+        match self.input.first() {
+            Some(&byte) if byte == STREAMED_END[0] => {
+                self.next_byte()?;
+                self.expect_crlf()?;
+                Ok(true)
             }
-            _ => Err(Error::UnexpectedByte {
-                expected: "A string or number prefix".to_string(),
-                found: char::from(first),
-            }),
-        }?;
-        Ok(result)
+            Some(_) => Ok(false),
+            None => Err(Error::UnexpectedEnd),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<Cow<'de, str>> {
+        match self.parse_bytes()? {
+            Cow::Borrowed(bytes) => Ok(Cow::Borrowed(str::from_utf8(bytes)?)),
+            Cow::Owned(bytes) => Ok(Cow::Owned(String::from_utf8(bytes)?)),
+        }
     }
 
-    fn parse_simple_string(&mut self) -> Result<&str> {
+    /// Consumes the rest of a simple (single line) value up to and including the CRLF.
+    fn parse_simple_bytes(&mut self) -> Result<&'de [u8]> {
         let crlf_index = self.input.windows(2).position(|w| w == CRLF);
         let result = if let Some(index) = crlf_index {
             let result = &self.input[..index];
@@ -104,29 +325,180 @@ impl<'de> Deserializer<'de> {
         } else {
             return Err(Error::UnexpectedEnd);
         };
-        if result.is_empty() {
-            return Err(Error::UnexpectedEnd);
-        }
         self.expect_crlf()?;
-        Ok(str::from_utf8(result)?)
+        Ok(result)
     }
 
-    fn parse_bulk_string(&mut self) -> Result<&str> {
+    /// Consumes the rest of a bulk value, i.e. `<length>\r\n<data>\r\n`.
+    ///
+    /// Streamed strings (`?\r\n` followed by chunks) are joined into a single copy.
+    fn parse_bulk_bytes(&mut self) -> Result<Cow<'de, [u8]>> {
         if self.input.starts_with(b"-1\r\n") {
             self.input = &self.input[4..]; // Skip -1\r\n
-            return Ok(""); // Null string
+            return Ok(Cow::Borrowed(b"")); // Null string
+        }
+        if self.input.first() == Some(&STREAMED) {
+            self.next_byte()?;
+            self.expect_crlf()?;
+            return self.parse_chunks().map(Cow::Owned);
         }
         let length = self.expect_length()?;
         self.expect_crlf()?;
-        let data = &self.input[..length];
-        self.input = &self.input[length..];
+        self.parse_payload(length).map(Cow::Borrowed)
+    }
+
+    /// Consumes the chunks of a streamed string, `;<length>\r\n<data>\r\n` up to a
+    /// chunk of length 0 (which has no data).
+    fn parse_chunks(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        while let Some(chunk) = self.parse_chunk()? {
+            data.extend_from_slice(chunk);
+        }
+        Ok(data)
+    }
+
+    /// Consumes the next chunk of a streamed string, `None` for the last (empty) chunk.
+    fn parse_chunk(&mut self) -> Result<Option<&'de [u8]>> {
+        self.expect_byte(STREAMED_CHUNK)?;
+        let length = self.expect_length()?;
         self.expect_crlf()?;
-        Ok(str::from_utf8(data)?)
+        if length == 0 {
+            return Ok(None);
+        }
+        self.parse_payload(length).map(Some)
+    }
+
+    /// Consumes `length` bytes of data and the CRLF after them.
+    fn parse_payload(&mut self, length: usize) -> Result<&'de [u8]> {
+        if self.input.len() < length {
+            return Err(Error::UnexpectedEnd);
+        }
+        let (data, rest) = self.input.split_at(length);
+        self.input = rest;
+        self.expect_crlf()?;
+        Ok(data)
+    }
+
+    /// Consumes any string or number value and returns its raw data, which is not required to be UTF-8.
+    fn parse_bytes(&mut self) -> Result<Cow<'de, [u8]>> {
+        let first = self.next_byte()?;
+        let kind = RespDataKind::try_from(first).map_err(|()| Error::UnrecognizedStart)?;
+        match kind {
+            RespDataKind::SimpleString
+            | RespDataKind::SimpleError
+            | RespDataKind::Integer
+            | RespDataKind::BigNumber
+            | RespDataKind::Float => self.parse_simple_bytes().map(Cow::Borrowed),
+            RespDataKind::BulkString | RespDataKind::BulkError | RespDataKind::VerbatimString => {
+                self.parse_bulk_bytes()
+            }
+            _ => Err(Error::UnexpectedByte {
+                expected: "A string or number prefix".to_string(),
+                found: char::from(first),
+            }),
+        }
+    }
+
+    /// Peeks at the kind of the next value without consuming any input.
+    ///
+    /// The RESP2 null bulk string (`$-1\r\n`) and null array (`*-1\r\n`)
+    /// are reported as [`RespDataKind::Null`].
+    fn peek_kind(&self) -> Result<RespDataKind> {
+        let first = self.input.first().ok_or(Error::UnexpectedEnd)?;
+        let kind = RespDataKind::try_from(*first).map_err(|()| Error::UnrecognizedStart)?;
+        if matches!(kind, RespDataKind::BulkString | RespDataKind::Array)
+            && self.input[1..].starts_with(b"-1\r\n")
+        {
+            Ok(RespDataKind::Null)
+        } else {
+            Ok(kind)
+        }
+    }
+
+    /// Consumes a null value, either RESP3 `_\r\n` or one of the RESP2 nulls.
+    fn parse_null(&mut self) -> Result<()> {
+        match self.next_byte()? {
+            b'_' => self.expect_crlf(),
+            b'$' | b'*' if self.input.starts_with(b"-1\r\n") => {
+                self.input = &self.input[4..];
+                Ok(())
+            }
+            b => Err(Error::UnexpectedByte {
+                expected: "A null value".to_string(),
+                found: char::from(b),
+            }),
+        }
+    }
+
+    /// Consumes the next value if it's a scalar, or only the header if it's an aggregate.
+    ///
+    /// This is the lowest level of parsing, where values are split but not decoded.
+    pub(crate) fn next_header(&mut self) -> Result<Header<'de>> {
+        self.parse_header(true)
+    }
+
+    /// Like `next_header`, but never allocates: the chunks of streamed strings are
+    /// skipped instead of joined, and reported with no data.
+    pub(crate) fn skip_header(&mut self) -> Result<Header<'de>> {
+        self.parse_header(false)
+    }
+
+    fn parse_header(&mut self, join_chunks: bool) -> Result<Header<'de>> {
+        if self.parse_streamed_end()? {
+            return Ok(Header::End);
+        }
+        let kind = self.peek_kind()?;
+        match kind {
+            RespDataKind::Null => {
+                self.parse_null()?;
+                Ok(Header::Scalar(kind, Cow::Borrowed(b"")))
+            }
+            RespDataKind::Boolean => {
+                self.next_byte()?;
+                let data = self.parse_simple_bytes()?;
+                if data != b"t" && data != b"f" {
+                    return Err(Error::UnexpectedByte {
+                        expected: "One of `t` or `f`".to_string(),
+                        found: char::from(data.first().copied().unwrap_or(b'\r')),
+                    });
+                }
+                Ok(Header::Scalar(kind, Cow::Borrowed(data)))
+            }
+            RespDataKind::Array
+            | RespDataKind::Set
+            | RespDataKind::Push
+            | RespDataKind::Map
+            | RespDataKind::Attributes => {
+                self.next_byte()?;
+                let length = self.expect_aggregate_length()?;
+                self.expect_crlf()?;
+                Ok(Header::Aggregate(kind, length))
+            }
+            RespDataKind::BulkString | RespDataKind::BulkError | RespDataKind::VerbatimString
+                if !join_chunks && self.input.get(1) == Some(&STREAMED) =>
+            {
+                self.input = &self.input[2..];
+                self.expect_crlf()?;
+                while self.parse_chunk()?.is_some() {}
+                Ok(Header::Scalar(kind, Cow::Borrowed(b"")))
+            }
+            _ => Ok(Header::Scalar(kind, self.parse_bytes()?)),
+        }
+    }
+
+    /// Consumes one complete value, including everything nested in it, without decoding it.
+    pub(crate) fn skip_frame(&mut self) -> Result<()> {
+        let mut pending = Pending::new();
+        while !pending.is_done() {
+            pending.push(&self.skip_header()?)?;
+        }
+        Ok(())
     }
 
     /// Parse an number from the RESP format.
     /// The integer format is: :<value>\r\n
     /// The float format is: ,[<+|->]<integral>[.<fractional>][<E|e>[sign]<exponent>]\r\n
+    /// or one of ,inf\r\n ,-inf\r\n and ,nan\r\n
     /// The big number format is: ([+|-]<number>\r\n
     fn parse_number<N>(&mut self) -> Result<N>
     where
@@ -143,19 +515,47 @@ impl<'de> Deserializer<'de> {
                 found: char::from(first),
             });
         }
-        let non_numeric_index = self
-            .input
-            .iter()
-            .position(|b| !VALID_NUMERIC_CHARS.contains(b))
-            .ok_or(Error::UnexpectedEnd)?;
-        let value_str = str::from_utf8(&self.input[..non_numeric_index])?;
-        self.input = &self.input[non_numeric_index..];
-        let value = value_str.parse::<N>().map_err(|_| Error::UnexpectedByte {
-            expected: "A valid integer string".to_string(),
-            found: value_str.chars().next().unwrap_or_default(),
-        })?;
-        self.expect_crlf()?;
-        Ok(value)
+        let data = self.parse_simple_bytes()?;
+        crate::frame::parse_number(data)
+    }
+
+    /// Parses a 128-bit integer from an integer, a big number, or a simple or bulk string
+    /// of digits (big numbers are often sent as strings).
+    fn parse_wide_integer<N>(&mut self) -> Result<N>
+    where
+        N: std::str::FromStr<Err = std::num::ParseIntError>,
+    {
+        match self.peek_kind()? {
+            RespDataKind::Integer
+            | RespDataKind::BigNumber
+            | RespDataKind::SimpleString
+            | RespDataKind::BulkString => crate::frame::parse_wide_integer(&self.parse_bytes()?),
+            _ => Err(Error::UnexpectedByte {
+                expected: "An integer (:), big number ((), or string prefix".to_string(),
+                found: char::from(self.input[0]),
+            }),
+        }
+    }
+
+    /// Visits a big number with the smallest integer type it fits in, or as a string if
+    /// it doesn't fit any of them.
+    fn deserialize_big_number<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.expect_byte(RespDataKind::BigNumber.to_prefix_bytes())?;
+        let digits = str::from_utf8(self.parse_simple_bytes()?)?;
+        if let Ok(v) = digits.parse() {
+            visitor.visit_i64(v)
+        } else if let Ok(v) = digits.parse() {
+            visitor.visit_u64(v)
+        } else if let Ok(v) = digits.parse() {
+            visitor.visit_i128(v)
+        } else if let Ok(v) = digits.parse() {
+            visitor.visit_u128(v)
+        } else {
+            visitor.visit_borrowed_str(digits)
+        }
     }
 }
 
@@ -174,6 +574,100 @@ where
     from_bytes(s.as_bytes())
 }
 
+/// An iterator over back-to-back values, see [`Deserializer::into_iter`].
+pub struct StreamDeserializer<'de, T> {
+    de: Deserializer<'de>,
+    /// The length of the whole input, to turn what remains into offsets.
+    length: usize,
+    failed: bool,
+    output: std::marker::PhantomData<T>,
+}
+
+impl<T> StreamDeserializer<'_, T> {
+    /// The offset of the first byte that wasn't consumed yet: the end of the last value
+    /// that was deserialized, or the whole input once it's all consumed.
+    ///
+    /// After an error, this is where the failed value started.
+    #[must_use]
+    pub const fn byte_offset(&self) -> usize {
+        self.length - self.de.remaining()
+    }
+}
+
+impl<'de, T> Iterator for StreamDeserializer<'de, T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    type Item = Result<(usize, T)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.de.is_empty() {
+            return None;
+        }
+        let offset = self.byte_offset();
+        let input = self.de.input;
+        match T::deserialize(&mut self.de) {
+            Ok(value) => Some(Ok((offset, value))),
+            Err(err) => {
+                self.failed = true;
+                self.de.input = input;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// A lazy iterator over the elements of an array, see [`Deserializer::iter_array`].
+pub struct ArrayIter<'a, 'de, T> {
+    de: &'a mut Deserializer<'de>,
+    /// The number of elements left, `None` for streamed arrays which end with a marker.
+    remaining: Option<usize>,
+    failed: bool,
+    output: std::marker::PhantomData<T>,
+}
+
+impl<'de, T> ArrayIter<'_, 'de, T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    fn next_element(&mut self) -> Result<Option<T>> {
+        match &mut self.remaining {
+            Some(0) => return Ok(None),
+            Some(remaining) => *remaining -= 1,
+            None if self.de.parse_streamed_end()? => {
+                self.remaining = Some(0);
+                return Ok(None);
+            }
+            None => {}
+        }
+        T::deserialize(&mut *self.de).map(Some)
+    }
+}
+
+impl<'de, T> Iterator for ArrayIter<'_, 'de, T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_element();
+        self.failed = next.is_err();
+        next.transpose()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            _ if self.failed => (0, Some(0)),
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (0, None),
+        }
+    }
+}
+
 impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     type Error = Error;
 
@@ -196,7 +690,7 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
             RespDataKind::Null => self.deserialize_unit(visitor),
             RespDataKind::Boolean => self.deserialize_bool(visitor),
             RespDataKind::Float => self.deserialize_f64(visitor),
-            RespDataKind::BigNumber => self.deserialize_i128(visitor),
+            RespDataKind::BigNumber => self.deserialize_big_number(visitor),
             RespDataKind::Map | RespDataKind::Attributes => self.deserialize_map(visitor),
         }
     }
@@ -276,6 +770,20 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
         visitor.visit_u64(self.parse_number::<u64>()?)
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_i128(self.parse_wide_integer()?)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_u128(self.parse_wide_integer()?)
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
@@ -314,8 +822,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        let s = self.parse_string()?;
-        visitor.visit_str(s)
+        match self.parse_string()? {
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+            Cow::Owned(s) => visitor.visit_string(s),
+        }
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
@@ -323,21 +833,25 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
         V: serde::de::Visitor<'de>,
     {
         let s = self.parse_string()?;
-        visitor.visit_string(s.to_string())
+        visitor.visit_string(s.into_owned())
     }
 
+    /// Same as `deserialize_str`, but the data is not required to be UTF-8.
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_bytes(self.input)
+        match self.parse_bytes()? {
+            Cow::Borrowed(b) => visitor.visit_borrowed_bytes(b),
+            Cow::Owned(b) => visitor.visit_byte_buf(b),
+        }
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_bytes(self.input.as_ref())
+        self.deserialize_bytes(visitor)
     }
 
     /// The following is taken from the JSON documentation, and applies to RESP as well:
@@ -352,11 +866,9 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        if self
-            .input
-            .starts_with(&[RespDataKind::Null.to_prefix_bytes()])
-        {
-            self.deserialize_unit(visitor)
+        if self.peek_kind()? == RespDataKind::Null {
+            self.parse_null()?;
+            visitor.visit_none()
         } else {
             visitor.visit_some(self)
         }
@@ -385,10 +897,23 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     /// As is done here, serializers are encouraged to treat newtype structs as
     /// insignificant wrappers around the data they contain. That means not
     /// parsing anything other than the contained value.
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    ///
+    /// The exceptions are [`crate::Value`], which asks for the kind of the
+    /// next value to be exposed as an enum variant (see `KindAccess`), and
+    /// [`crate::RawResp`], which asks for the raw bytes of the next value.
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
     {
+        if name == crate::value::TOKEN {
+            return visitor.visit_enum(KindAccess::new(self));
+        }
+        if name == crate::raw::TOKEN {
+            let start = self.input;
+            self.skip_frame()?;
+            let frame = &start[..start.len() - self.input.len()];
+            return visitor.visit_map(crate::raw::RawAccess(Some(frame)));
+        }
         visitor.visit_newtype_struct(self)
     }
 
@@ -401,18 +926,24 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         let first = self.input.first().ok_or(Error::UnexpectedEnd)?;
         let kind = RespDataKind::try_from(*first).map_err(|()| Error::UnrecognizedStart)?;
-        if !matches!(kind, RespDataKind::Array | RespDataKind::Set | RespDataKind::Push) {
+        if !matches!(
+            kind,
+            RespDataKind::Array | RespDataKind::Set | RespDataKind::Push
+        ) {
             return Err(Error::UnexpectedByte {
                 expected: "An array, set, or push prefix".to_string(),
                 found: char::from(*first),
             });
         }
         self.expect_byte(*first)?;
-        let length = self.expect_length()?;
+        if kind == RespDataKind::Array && self.input.starts_with(b"-1\r\n") {
+            self.input = &self.input[4..]; // The RESP2 null array, read as an empty sequence
+            return visitor.visit_seq(LengthSeqVisitor::new(self, Some(0)));
+        }
+        let length = self.expect_aggregate_length()?;
         self.expect_crlf()?;
         // We need to create a new visitor that can handle the sequence
-        let seq_visitor = LengthSeqVisitor::new(self, length);
-        visitor.visit_seq(seq_visitor)
+        self.nested(|de| visitor.visit_seq(LengthSeqVisitor::new(de, length)))
     }
 
     // Tuples look just like sequences. Some formats may be able to
@@ -457,11 +988,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
             });
         }
         self.expect_byte(*first)?;
-        let length = self.expect_length()?;
+        let length = self.expect_aggregate_length()?;
         self.expect_crlf()?;
 
-        let seq_visitor = LengthSeqVisitor::new(self, length);
-        visitor.visit_map(seq_visitor)
+        self.nested(|de| visitor.visit_map(LengthSeqVisitor::new(de, length)))
     }
 
     // Structs look just like maps in RESP.
@@ -501,10 +1031,10 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
             | RespDataKind::VerbatimString => {
                 // Visit a unit variant.
                 let s = self.parse_string()?;
-                visitor.visit_enum(s.into_deserializer())
+                visitor.visit_enum(s.as_ref().into_deserializer())
             }
             RespDataKind::Map | RespDataKind::Attributes => {
-                visitor.visit_enum(EnumDeserializer::new(self))
+                self.nested(|de| visitor.visit_enum(EnumDeserializer::new(de)))
             }
             _ => Err(Error::UnexpectedByte {
                 expected: "A string or map prefix".to_string(),
@@ -544,18 +1074,27 @@ impl<'de> serde::de::Deserializer<'de> for &mut Deserializer<'de> {
 
 struct LengthSeqVisitor<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
-    length: usize,
+    /// `None` for streamed aggregates, which end with a marker instead.
+    length: Option<usize>,
     current: usize,
 }
 
 impl<'a, 'de> LengthSeqVisitor<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>, length: usize) -> Self {
+    fn new(de: &'a mut Deserializer<'de>, length: Option<usize>) -> Self {
         Self {
             de,
             length,
             current: 0,
         }
     }
+
+    /// Checks if we have reached the end of the sequence, consuming the end marker if streamed.
+    fn is_done(&mut self) -> Result<bool> {
+        match self.length {
+            Some(length) => Ok(self.current >= length),
+            None => self.de.parse_streamed_end(),
+        }
+    }
 }
 
 // `SeqAccess` is provided to the `Visitor` to give it the ability to iterate
@@ -567,8 +1106,7 @@ impl<'de> serde::de::SeqAccess<'de> for LengthSeqVisitor<'_, 'de> {
     where
         T: serde::de::DeserializeSeed<'de>,
     {
-        // Check if we have reached the end of the sequence.
-        if self.current >= self.length {
+        if self.is_done()? {
             return Ok(None);
         }
         self.current += 1;
@@ -587,8 +1125,7 @@ impl<'de> serde::de::MapAccess<'de> for LengthSeqVisitor<'_, 'de> {
     where
         K: serde::de::DeserializeSeed<'de>,
     {
-        // Check if we have reached the end of the sequence.
-        if self.current >= self.length {
+        if self.is_done()? {
             return Ok(None);
         }
         self.current += 1;
@@ -607,11 +1144,26 @@ impl<'de> serde::de::MapAccess<'de> for LengthSeqVisitor<'_, 'de> {
 
 struct EnumDeserializer<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
+    /// Whether the map is streamed, so the end marker follows the variant content.
+    streamed: bool,
 }
 
 impl<'a, 'de> EnumDeserializer<'a, 'de> {
     const fn new(de: &'a mut Deserializer<'de>) -> Self {
-        Self { de }
+        Self {
+            de,
+            streamed: false,
+        }
+    }
+
+    /// Consumes the end marker of a streamed map after the variant content.
+    fn end<T>(self, value: T) -> Result<T> {
+        if self.streamed && !self.de.parse_streamed_end()? {
+            return Err(Error::DeserializeError(
+                "Expected a single key-value pair for enum variant".to_string(),
+            ));
+        }
+        Ok(value)
     }
 }
 
@@ -624,7 +1176,7 @@ impl<'de> serde::de::EnumAccess<'de> for EnumDeserializer<'_, 'de> {
     type Error = Error;
     type Variant = Self;
 
-    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self::Variant)>
     where
         V: serde::de::DeserializeSeed<'de>,
     {
@@ -637,8 +1189,9 @@ impl<'de> serde::de::EnumAccess<'de> for EnumDeserializer<'_, 'de> {
             });
         }
         self.de.expect_byte(*first)?;
-        let length = self.de.expect_length()?;
-        if length != 1 {
+        let length = self.de.expect_aggregate_length()?;
+        self.streamed = length.is_none();
+        if length.is_some_and(|length| length != 1) {
             return Err(Error::DeserializeError(
                 "Expected a single key-value pair for enum variant".to_string(),
             ));
@@ -668,7 +1221,8 @@ impl<'de> serde::de::VariantAccess<'de> for EnumDeserializer<'_, 'de> {
     where
         T: serde::de::DeserializeSeed<'de>,
     {
-        seed.deserialize(self.de)
+        let value = seed.deserialize(&mut *self.de)?;
+        self.end(value)
     }
 
     // Tuple variants are represented as `{ NAME: [DATA...] }` so
@@ -677,11 +1231,74 @@ impl<'de> serde::de::VariantAccess<'de> for EnumDeserializer<'_, 'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        serde::de::Deserializer::deserialize_seq(self.de, visitor)
+        let value = serde::de::Deserializer::deserialize_seq(&mut *self.de, visitor)?;
+        self.end(value)
     }
 
     // Struct variants are represented as `{ NAME: { K: V, ... } }` so
     // deserialize the inner map here.
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let value = serde::de::Deserializer::deserialize_map(&mut *self.de, visitor)?;
+        self.end(value)
+    }
+}
+
+/// Exposes the RESP kind of the next value as the variant of an enum, with the
+/// value itself as its content. Used by [`crate::Value`] to keep apart kinds that
+/// look the same through the regular serde data model (e.g. simple and bulk strings).
+struct KindAccess<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl<'a, 'de> KindAccess<'a, 'de> {
+    const fn new(de: &'a mut Deserializer<'de>) -> Self {
+        Self { de }
+    }
+}
+
+// The variant identifier is the prefix character of the kind, the input is not
+// consumed so the variant content can be deserialized as a complete value.
+impl<'de> serde::de::EnumAccess<'de> for KindAccess<'_, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let kind = self.de.peek_kind()?;
+        let prefix: serde::de::value::CharDeserializer<Error> =
+            kind.to_prefix_char().into_deserializer();
+        let val = seed.deserialize(prefix)?;
+        Ok((val, self))
+    }
+}
+
+impl<'de> serde::de::VariantAccess<'de> for KindAccess<'_, 'de> {
+    type Error = Error;
+
+    // Only nulls have no content
+    fn unit_variant(self) -> Result<()> {
+        self.de.parse_null()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        serde::de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
     fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
     where
         V: serde::de::Visitor<'de>,
@@ -702,7 +1319,7 @@ mod tests {
         assert_eq!(from_str::<u32>(s).unwrap(), v as u32, "u32");
         assert_eq!(from_str::<u64>(s).unwrap(), v as u64, "u64");
         assert_eq!(from_str::<usize>(s).unwrap(), v as usize, "usize");
-        assert!(from_str::<u128>(s).is_err(), "u128");
+        assert_eq!(from_str::<u128>(s).unwrap(), v as u128, "u128");
     }
 
     fn test_i(s: &str, v: i8) {
@@ -711,7 +1328,7 @@ mod tests {
         assert_eq!(from_str::<i32>(s).unwrap(), v as i32, "i32");
         assert_eq!(from_str::<i64>(s).unwrap(), v as i64, "i64");
         assert_eq!(from_str::<isize>(s).unwrap(), v as isize, "isize");
-        assert!(from_str::<i128>(s).is_err(), "i128");
+        assert_eq!(from_str::<i128>(s).unwrap(), v as i128, "i128");
     }
 
     #[test]
@@ -752,6 +1369,11 @@ mod tests {
         let raw = ",2e20\r\n";
         assert_eq!(from_str::<f32>(raw).unwrap(), 2e20, "f32");
         assert_eq!(from_str::<f64>(raw).unwrap(), 2e20, "f64");
+        assert_eq!(from_str::<f64>(",inf\r\n").unwrap(), f64::INFINITY);
+        assert_eq!(from_str::<f32>(",-inf\r\n").unwrap(), f32::NEG_INFINITY);
+        assert!(from_str::<f64>(",nan\r\n").unwrap().is_nan());
+        assert!(from_str::<f64>(",1.5x\r\n").is_err());
+        assert!(from_str::<f64>(",1.5").is_err(), "No CRLF");
     }
 
     #[test]
@@ -829,6 +1451,21 @@ mod tests {
             vec![Some(1), None],
             "Array of Option<u8>"
         );
+
+        assert_eq!(
+            from_str::<Vec<u8>>("*-1\r\n").unwrap(),
+            Vec::<u8>::new(),
+            "RESP2 null array"
+        );
+        assert_eq!(
+            from_str::<(Option<Vec<u8>>, Option<String>)>("*2\r\n*-1\r\n$-1\r\n").unwrap(),
+            (None, None),
+            "RESP2 nulls as Option"
+        );
+        assert!(matches!(
+            from_str::<Vec<u8>>("*-1\r"),
+            Err(Error::UnexpectedEnd)
+        ));
     }
 
     #[test]
@@ -893,4 +1530,141 @@ mod tests {
         let expected = E::Struct { a: 1 };
         assert_eq!(expected, from_str(raw).unwrap());
     }
+
+    #[test]
+    fn test_truncated() {
+        let raw = b"*5\r\n$5\r\nHello\r\n%1\r\n+a\r\n#t\r\n$-1\r\n=7\r\ntxt:abc\r\n\
+            *?\r\n$?\r\n;1\r\na\r\n;0\r\n.\r\n";
+        assert!(from_bytes::<crate::Value>(raw).is_ok());
+        for end in 0..raw.len() {
+            let input = &raw[..end];
+            let result = from_bytes::<crate::Value>(input);
+            assert!(
+                matches!(result, Err(Error::UnexpectedEnd)),
+                "{end}: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        let raw = b"*2\r\n:1\r\n:2\r\n*1\r\n:3\r\n*0\r\n";
+        let mut stream = Deserializer::new(raw).into_iter::<Vec<u8>>();
+        assert_eq!(stream.next().unwrap().unwrap(), (0, vec![1, 2]));
+        assert_eq!(stream.byte_offset(), 12);
+        assert_eq!(stream.next().unwrap().unwrap(), (12, vec![3]));
+        assert_eq!(stream.next().unwrap().unwrap(), (20, vec![]));
+        assert!(stream.next().is_none());
+        assert_eq!(stream.byte_offset(), raw.len());
+
+        let raw = b":1\r\n:2\r\n:3";
+        let mut stream = Deserializer::new(raw).into_iter::<i64>();
+        assert_eq!(stream.next().unwrap().unwrap(), (0, 1));
+        assert_eq!(stream.next().unwrap().unwrap(), (4, 2));
+        assert!(matches!(stream.next(), Some(Err(Error::UnexpectedEnd))));
+        assert_eq!(stream.byte_offset(), 8, "The start of the incomplete value");
+        assert!(stream.next().is_none(), "Stops after an error");
+    }
+
+    #[test]
+    fn test_128() {
+        let max = "340282366920938463463374607431768211455";
+        assert_eq!(from_str::<u128>(&format!("({max}\r\n")).unwrap(), u128::MAX);
+        assert_eq!(from_str::<u128>(&format!("+{max}\r\n")).unwrap(), u128::MAX);
+        assert_eq!(
+            from_str::<u128>(&format!("${}\r\n{max}\r\n", max.len())).unwrap(),
+            u128::MAX
+        );
+        let min = "-170141183460469231731687303715884105728";
+        assert_eq!(from_str::<i128>(&format!("({min}\r\n")).unwrap(), i128::MIN);
+        assert_eq!(from_str::<i128>(":-5\r\n").unwrap(), -5);
+
+        let err = from_str::<i128>(&format!("({max}\r\n")).unwrap_err();
+        assert!(err.to_string().contains("out of range for i128"), "{err}");
+        assert!(from_str::<u128>(&format!("({max}0\r\n")).is_err());
+        assert!(from_str::<u128>(":-1\r\n").is_err());
+        assert!(from_str::<u128>("+abc\r\n").is_err());
+        assert!(from_str::<u128>(",1.5\r\n").is_err());
+        assert!(from_str::<u128>("*0\r\n").is_err());
+
+        // Too big for any integer type, but still accepted by `deserialize_any`
+        let huge = format!("({max}{max}\r\n");
+        assert!(from_str::<serde::de::IgnoredAny>(&huge).is_ok());
+    }
+
+    #[test]
+    fn test_iter_array() {
+        let raw = b"*3\r\n:1\r\n:2\r\n:3\r\n+OK\r\n";
+        let mut deserializer = Deserializer::new(raw);
+        let numbers = deserializer.iter_array::<u8>().unwrap();
+        assert_eq!(numbers.collect::<Result<Vec<_>>>().unwrap(), [1, 2, 3]);
+        assert_eq!(
+            String::deserialize(&mut deserializer).unwrap(),
+            "OK",
+            "Continues after the array"
+        );
+
+        let raw = b"*?\r\n:1\r\n:2\r\n.\r\n:3\r\n";
+        let mut deserializer = Deserializer::new(raw);
+        let numbers = deserializer.iter_array::<u8>().unwrap();
+        assert_eq!(numbers.size_hint(), (0, None));
+        assert_eq!(numbers.collect::<Result<Vec<_>>>().unwrap(), [1, 2]);
+        assert_eq!(u8::deserialize(&mut deserializer).unwrap(), 3);
+
+        for raw in [&b"*-1\r\n"[..], b"_\r\n", b"*0\r\n"] {
+            let mut deserializer = Deserializer::new(raw);
+            assert_eq!(deserializer.iter_array::<u8>().unwrap().count(), 0);
+        }
+
+        let mut deserializer = Deserializer::new(b"*3\r\n:1\r\n:300\r\n:3\r\n");
+        let mut numbers = deserializer.iter_array::<u8>().unwrap();
+        assert_eq!(numbers.next().unwrap().unwrap(), 1);
+        assert!(numbers.next().unwrap().is_err());
+        assert!(numbers.next().is_none(), "Stops after an error");
+        assert!(Deserializer::new(b"%0\r\n").iter_array::<u8>().is_err());
+    }
+
+    #[test]
+    fn test_streamed() {
+        let raw = "$?\r\n;4\r\nHell\r\n;1\r\no\r\n;0\r\n";
+        assert_eq!(from_str::<String>(raw).unwrap(), "Hello");
+        assert!(
+            from_str::<&str>(raw).is_err(),
+            "Joined chunks can't be borrowed"
+        );
+
+        let raw = "*?\r\n:1\r\n*?\r\n.\r\n:2\r\n.\r\n";
+        assert_eq!(
+            from_str::<(u8, Vec<u8>, u8)>(raw).unwrap(),
+            (1, vec![], 2),
+            "Nested streamed arrays"
+        );
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        enum E {
+            Newtype(String),
+            Struct { a: u32 },
+        }
+
+        let raw = "%?\r\n+a\r\n:1\r\n.\r\n";
+        let expected: HashMap<String, u32> = [("a".to_string(), 1)].into();
+        assert_eq!(from_str::<HashMap<String, u32>>(raw).unwrap(), expected);
+        assert_eq!(
+            from_str::<E>("%?\r\n+Struct\r\n%?\r\n+a\r\n:1\r\n.\r\n.\r\n").unwrap(),
+            E::Struct { a: 1 }
+        );
+        assert_eq!(
+            from_str::<E>("%?\r\n+Newtype\r\n$?\r\n;1\r\nx\r\n;0\r\n.\r\n").unwrap(),
+            E::Newtype("x".to_string())
+        );
+        assert!(from_str::<E>("%?\r\n+Newtype\r\n+x\r\n+y\r\n").is_err());
+        assert!(from_str::<Vec<u8>>("*?\r\n:1\r\n").is_err());
+        assert!(from_str::<String>("$?\r\n;1\r\nab\r\n;0\r\n").is_err());
+
+        let value = crate::Value::Array(vec![crate::Value::BulkString(b"Hello".to_vec())]);
+        assert_eq!(
+            from_str::<crate::Value>("*?\r\n$?\r\n;4\r\nHell\r\n;1\r\no\r\n;0\r\n.\r\n").unwrap(),
+            value
+        );
+    }
 }
@@ -0,0 +1,413 @@
+//! Decoding RESP values from a buffer that is filled over time, e.g. from a socket.
+
+use crate::de::Pending;
+use crate::frame::check_scalar;
+use crate::read::MAX_LINE_LENGTH;
+use crate::{CRLF, Deserializer, Error, Event, Events, RespDataKind, Result};
+use serde::Deserialize;
+
+/// The result of [`Decoder::decode`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decoded<T> {
+    /// A complete value, and the number of bytes it took from the start of the buffer.
+    Complete(T, usize),
+    /// The buffer ends before the value does.
+    ///
+    /// `needed` is the least number of bytes still missing, when that's known
+    /// (i.e. in the middle of a bulk string). More may be needed after them.
+    Incomplete { needed: Option<usize> },
+}
+
+/// Decodes RESP values from a buffer that may hold only part of a value.
+///
+/// When the buffer is incomplete, the decoder remembers how far it got, so calling it again
+/// after appending more bytes continues from there instead of scanning the buffer again.
+/// Only the structure of the value is checked until it's complete, and then it's deserialized.
+///
+/// ```
+/// # use rediserde::{Decoded, Decoder};
+/// let mut decoder = Decoder::new();
+/// let mut buffer = b"*2\r\n$5\r\nHel".to_vec();
+/// let decoded = decoder.decode::<Vec<String>>(&buffer).unwrap();
+/// assert_eq!(decoded, Decoded::Incomplete { needed: Some(4) });
+///
+/// buffer.extend_from_slice(b"lo\r\n+World\r\n:1\r\n");
+/// let Decoded::Complete(value, consumed) = decoder.decode::<Vec<String>>(&buffer).unwrap() else {
+///     panic!("Expected a complete value");
+/// };
+/// assert_eq!(value, vec!["Hello", "World"]);
+/// buffer.drain(..consumed);
+/// assert_eq!(buffer, b":1\r\n");
+/// ```
+///
+/// Between calls, bytes may only be appended to the buffer until a value is complete.
+/// Errors reset the decoder, but the buffer is then left in an unknown state.
+///
+/// Like the readers, a line longer than 64 KiB (like a never ending simple string) fails
+/// with [`Error::LineTooLong`].
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    /// The end of the last value (or aggregate header) that was checked.
+    checked: usize,
+    /// How many bytes after `checked` were searched for the end of an unfinished line.
+    searched: usize,
+    /// The values still missing, including nested ones.
+    pending: Pending,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            checked: 0,
+            searched: 0,
+            pending: Pending::new(),
+        }
+    }
+
+    /// Decodes the value at the start of `input`, if it's complete.
+    pub fn decode<'de, T>(&mut self, input: &'de [u8]) -> Result<Decoded<T>>
+    where
+        T: Deserialize<'de>,
+    {
+        match self.decode_len(input)? {
+            Decoded::Complete((), consumed) => {
+                let value = crate::from_bytes(&input[..consumed])?;
+                Ok(Decoded::Complete(value, consumed))
+            }
+            Decoded::Incomplete { needed } => Ok(Decoded::Incomplete { needed }),
+        }
+    }
+
+    /// Like `decode`, but only finds the length of the value without deserializing it.
+    pub(crate) fn decode_len(&mut self, input: &[u8]) -> Result<Decoded<()>> {
+        let consumed = match self.scan(input) {
+            Ok(Some(consumed)) => consumed,
+            Ok(None) => match bulk_needed(&input[self.checked..]) {
+                Ok(needed) => return Ok(Decoded::Incomplete { needed }),
+                Err(err) => {
+                    *self = Self::new();
+                    return Err(err);
+                }
+            },
+            Err(err) => {
+                *self = Self::new();
+                return Err(err);
+            }
+        };
+        *self = Self::new();
+        Ok(Decoded::Complete((), consumed))
+    }
+
+    /// Checks the structure of as many values as possible, returning the length of the
+    /// frame once it's complete.
+    fn scan(&mut self, input: &[u8]) -> Result<Option<usize>> {
+        if input.len() < self.checked {
+            *self = Self::new(); // Not the same buffer, start over
+        }
+        while !self.pending.is_done() {
+            let rest = &input[self.checked..];
+            if self.searched > 0 && self.line_unfinished(rest)? {
+                return Ok(None);
+            }
+            let mut deserializer = Deserializer::new(rest);
+            let header = match deserializer.skip_header() {
+                Ok(header) => header,
+                Err(Error::UnexpectedEnd) => {
+                    self.line_unfinished(rest)?;
+                    return Ok(None);
+                }
+                Err(err) => return Err(err),
+            };
+            self.pending.push(&header)?;
+            self.checked = input.len() - deserializer.remaining();
+        }
+        Ok(Some(self.checked))
+    }
+
+    /// Whether the first line of `rest` has no end yet. Only the bytes that weren't searched
+    /// in an earlier call are searched, so a long line arriving in small pieces stays cheap.
+    fn line_unfinished(&mut self, rest: &[u8]) -> Result<bool> {
+        let searched = self.searched.min(rest.len());
+        if rest[searched..].windows(2).any(|w| w == CRLF) {
+            self.searched = 0;
+            return Ok(false);
+        }
+        // The last byte may be the CR of a CRLF that is still arriving
+        self.searched = rest.len().saturating_sub(1);
+        if self.searched > MAX_LINE_LENGTH {
+            return Err(Error::LineTooLong);
+        }
+        Ok(true)
+    }
+}
+
+/// Finds the length of the value at the start of `input`, without decoding it.
+///
+/// Returns `None` if `input` ends before the value does. Nested aggregates are walked with the
+/// same rules as [`Deserializer`], but the contents of scalars (like the digits of an integer) are
+/// not decoded, and nothing is allocated (except to track streamed aggregates nested in
+/// aggregates of known length), so this is cheap enough to frame every incoming value.
+///
+/// ```
+/// let input = b"*2\r\n$5\r\nHello\r\n:1\r\n+OK\r\n";
+/// assert_eq!(rediserde::frame_len(input).unwrap(), Some(19));
+/// assert_eq!(rediserde::frame_len(&input[..10]).unwrap(), None);
+/// assert!(rediserde::frame_len(b"*2\r\n?\r\n").is_err());
+/// ```
+pub fn frame_len(input: &[u8]) -> Result<Option<usize>> {
+    Decoder::new().scan(input)
+}
+
+/// Checks that `input` is exactly one complete value that [`crate::parse_frame`] accepts,
+/// without building it.
+///
+/// Unlike [`frame_len`], this also checks the contents of scalars, like the digits of an
+/// integer or the encoding prefix of a verbatim string.
+///
+/// ```
+/// assert!(rediserde::validate(b"*1\r\n:1\r\n").is_ok());
+/// assert!(rediserde::validate(b"*1\r\n").is_err(), "Incomplete");
+/// assert!(rediserde::validate(b":1\r\n:2\r\n").is_err(), "More than one value");
+/// assert!(rediserde::validate(b":abc\r\n").is_err(), "Not an integer");
+/// ```
+pub fn validate(input: &[u8]) -> Result<()> {
+    match Decoder::new().scan(input)? {
+        Some(len) if len == input.len() => {}
+        Some(_) => {
+            return Err(Error::DeserializeError(
+                "Expected a single RESP value, found trailing bytes".to_string(),
+            ));
+        }
+        None => return Err(Error::UnexpectedEnd),
+    }
+    for event in Events::new(input) {
+        if let Event::Scalar(kind, data) = event? {
+            check_scalar(kind, &data)?;
+        }
+    }
+    Ok(())
+}
+
+/// The number of bytes missing from a bulk value that was cut short, if its header is complete.
+///
+/// A length so large that the value could never fit in memory is an error, instead of waiting
+/// for more input forever.
+fn bulk_needed(input: &[u8]) -> Result<Option<usize>> {
+    let Some(kind) = input.first().and_then(|&b| RespDataKind::try_from(b).ok()) else {
+        return Ok(None);
+    };
+    if !matches!(
+        kind,
+        RespDataKind::BulkString | RespDataKind::BulkError | RespDataKind::VerbatimString
+    ) {
+        return Ok(None);
+    }
+    let Some(header_end) = input.windows(2).position(|w| w == CRLF) else {
+        return Ok(None);
+    };
+    let Some(length) = str::from_utf8(&input[1..header_end])
+        .ok()
+        .and_then(|length| length.parse::<usize>().ok())
+    else {
+        return Ok(None);
+    };
+    let total = (header_end + CRLF.len())
+        .checked_add(length)
+        .and_then(|total| total.checked_add(CRLF.len()))
+        .ok_or(Error::ExpectedLength)?;
+    Ok(total.checked_sub(input.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    const RAW: &[u8] = b"*3\r\n$5\r\nHello\r\n%1\r\n+a\r\n*-1\r\n=7\r\ntxt:abc\r\n";
+
+    #[test]
+    fn test_byte_by_byte() {
+        let mut decoder = Decoder::new();
+        let mut buffer = Vec::new();
+        for (i, byte) in RAW.iter().enumerate() {
+            buffer.push(*byte);
+            let decoded = decoder.decode::<Value>(&buffer).unwrap();
+            if i < RAW.len() - 1 {
+                assert!(matches!(decoded, Decoded::Incomplete { .. }), "{i}");
+            } else {
+                let expected = crate::from_bytes(RAW).unwrap();
+                assert_eq!(decoded, Decoded::Complete(expected, RAW.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_needed() {
+        let mut decoder = Decoder::new();
+        let needed = |decoder: &mut Decoder, input: &[u8]| match decoder.decode::<Value>(input) {
+            Ok(Decoded::Incomplete { needed }) => needed,
+            other => panic!("Expected incomplete, got {other:?}"),
+        };
+        assert_eq!(needed(&mut decoder, b"*3\r\n$5"), None);
+        assert_eq!(needed(&mut decoder, b"*3\r\n$5\r\n"), Some(7));
+        assert_eq!(needed(&mut decoder, b"*3\r\n$5\r\nHello\r"), Some(1));
+        assert_eq!(needed(&mut decoder, b"*3\r\n$5\r\nHello\r\n"), None);
+        assert_eq!(decoder.checked, 15, "Checked values are not scanned again");
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let mut decoder = Decoder::new();
+        let input = b"+OK\r\n:1\r\n:2";
+        let Decoded::Complete(first, consumed) = decoder.decode::<String>(input).unwrap() else {
+            panic!("Expected a complete value");
+        };
+        assert_eq!((first.as_str(), consumed), ("OK", 5));
+        let input = &input[consumed..];
+        assert_eq!(
+            decoder.decode::<u8>(input).unwrap(),
+            Decoded::Complete(1, 4)
+        );
+        let input = &input[4..];
+        assert_eq!(
+            decoder.decode::<u8>(input).unwrap(),
+            Decoded::Incomplete { needed: None }
+        );
+    }
+
+    #[test]
+    fn test_streamed() {
+        let raw = b"*?\r\n$?\r\n;2\r\nab\r\n;0\r\n*1\r\n:1\r\n.\r\n";
+        let mut decoder = Decoder::new();
+        for end in 0..raw.len() {
+            let decoded = decoder.decode::<Value>(&raw[..end]).unwrap();
+            assert!(matches!(decoded, Decoded::Incomplete { .. }), "{end}");
+        }
+        let expected: Value = crate::from_bytes(raw).unwrap();
+        assert_eq!(
+            decoder.decode::<Value>(raw).unwrap(),
+            Decoded::Complete(expected, raw.len())
+        );
+        assert!(
+            decoder.decode::<Value>(b"*?\r\n*1\r\n.\r\n").is_err(),
+            "End marker inside `*1`"
+        );
+    }
+
+    #[test]
+    fn test_frame_len() {
+        let raw = b"*3\r\n*?\r\n:1\r\n*?\r\n.\r\n.\r\n$?\r\n;2\r\nab\r\n;0\r\n%1\r\n+a\r\n_\r\n";
+        for end in 0..raw.len() {
+            assert_eq!(frame_len(&raw[..end]).unwrap(), None, "{end}");
+            assert!(matches!(validate(&raw[..end]), Err(Error::UnexpectedEnd)));
+        }
+        let mut input = raw.to_vec();
+        input.extend_from_slice(b":1\r\n");
+        assert_eq!(frame_len(&input).unwrap(), Some(raw.len()));
+        assert!(validate(raw).is_ok());
+        assert!(validate(&input).is_err(), "A second value");
+        assert!(frame_len(b"*1\r\n.\r\n").is_err());
+        assert_eq!(frame_len(b"*?\r\n.\r\n.\r\n").unwrap(), Some(7));
+        assert!(
+            validate(b"*?\r\n.\r\n.\r\n").is_err(),
+            "End outside of the aggregate"
+        );
+        assert_eq!(
+            frame_len(b"%?\r\n+a\r\n|?\r\n+b\r\n:1\r\n.\r\n.\r\n").unwrap(),
+            Some(26)
+        );
+        for odd in [
+            &b"%?\r\n+a\r\n.\r\n"[..],
+            b"%?\r\n+a\r\n*?\r\n.\r\n+b\r\n.\r\n",
+            b"*?\r\n|?\r\n+a\r\n:1\r\n+b\r\n.\r\n.\r\n",
+        ] {
+            assert!(frame_len(odd).is_err(), "{odd:?}");
+            assert!(validate(odd).is_err(), "{odd:?}");
+            assert!(Decoder::new().decode::<Value>(odd).is_err(), "{odd:?}");
+        }
+    }
+
+    #[test]
+    fn test_validate_scalars() {
+        for valid in [
+            &b":-12\r\n"[..],
+            b",1.5e3\r\n",
+            b",inf\r\n",
+            b"=7\r\nmkd:abc\r\n",
+            b"*?\r\n(123\r\n!1\r\nx\r\n.\r\n",
+        ] {
+            assert!(validate(valid).is_ok(), "{valid:?}");
+            assert!(crate::from_bytes::<Value>(valid).is_ok(), "{valid:?}");
+        }
+        for invalid in [
+            &b":abc\r\n"[..],
+            b",zz\r\n",
+            b"=3\r\nabc\r\n",
+            b"+\xff\r\n",
+            b"*2\r\n:1\r\n:1.5\r\n",
+            b"%?\r\n+a\r\n.\r\n",
+        ] {
+            assert!(validate(invalid).is_err(), "{invalid:?}");
+            assert!(crate::from_bytes::<Value>(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_long_line() {
+        let mut decoder = Decoder::new();
+        let mut buffer = b"*2\r\n+".to_vec();
+        while buffer.len() < 1000 {
+            buffer.extend_from_slice(b"aaaa");
+            assert_eq!(
+                decoder.decode::<Value>(&buffer).unwrap(),
+                Decoded::Incomplete { needed: None }
+            );
+        }
+        buffer.push(b'\r');
+        assert_eq!(
+            decoder.decode::<Value>(&buffer).unwrap(),
+            Decoded::Incomplete { needed: None }
+        );
+        buffer.extend_from_slice(b"\n:1\r\n");
+        assert!(matches!(
+            decoder.decode::<Vec<Value>>(&buffer).unwrap(),
+            Decoded::Complete(values, consumed) if values.len() == 2 && consumed == buffer.len()
+        ));
+
+        let mut buffer = b"+".to_vec();
+        let chunk = [b'a'; 4096];
+        let error = loop {
+            buffer.extend_from_slice(&chunk);
+            match decoder.decode::<Value>(&buffer) {
+                Ok(decoded) => assert_eq!(decoded, Decoded::Incomplete { needed: None }),
+                Err(err) => break err,
+            }
+        };
+        assert!(matches!(error, Error::LineTooLong));
+        assert!(buffer.len() <= MAX_LINE_LENGTH + 2 * chunk.len());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut decoder = Decoder::new();
+        assert!(decoder.decode::<Value>(b"*2\r\n?").is_err());
+        assert!(decoder.decode::<Value>(b"$x\r\n").is_err());
+        assert!(decoder.decode::<u8>(b":300\r\n").is_err());
+        assert!(matches!(
+            decoder.decode::<Value>(b"$18446744073709551615\r\nab"),
+            Err(Error::ExpectedLength)
+        ));
+        assert_eq!(
+            decoder.decode::<u8>(b":3\r\n").unwrap(),
+            Decoded::Complete(3, 4),
+            "Errors reset the decoder"
+        );
+    }
+}
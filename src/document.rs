@@ -0,0 +1,348 @@
+//! Random access into a RESP value through an index of its structure, built in a single scan.
+
+use crate::de::Header;
+use crate::{Deserializer, Error, RespDataKind, Result};
+use serde::Deserialize;
+
+/// A RESP value indexed for random access, without decoding any of it up front.
+///
+/// Building the document scans the value once and records where every nested value starts
+/// and ends (a "tape"). After that, element `i` of any aggregate is found in constant time,
+/// and only the parts that are actually used are deserialized.
+///
+/// ```
+/// # use rediserde::{RespDataKind, RespDocument};
+/// let input = b"*3\r\n:1\r\n%2\r\n+name\r\n+Alice\r\n+age\r\n:30\r\n$5\r\nHello\r\n";
+/// let document = RespDocument::new(input).unwrap();
+/// let root = document.root();
+/// assert_eq!(root.len(), 3);
+/// assert_eq!(root.get(2).unwrap().deserialize::<&str>().unwrap(), "Hello");
+///
+/// let person = root.get(1).unwrap();
+/// assert_eq!(person.kind(), RespDataKind::Map);
+/// assert_eq!(person.get_key("age").unwrap().deserialize::<u8>().unwrap(), 30);
+/// assert_eq!(person.as_bytes(), b"%2\r\n+name\r\n+Alice\r\n+age\r\n:30\r\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct RespDocument<'de> {
+    input: &'de [u8],
+    /// Every value in the order they appear, the whole value first.
+    nodes: Vec<Node>,
+    /// The indexes of the direct children of each aggregate, next to each other.
+    children: Vec<usize>,
+}
+
+/// A value in the tape of a [`RespDocument`].
+#[derive(Debug, Clone)]
+struct Node {
+    kind: RespDataKind,
+    /// The range of the value in the input, including everything nested in it.
+    start: usize,
+    end: usize,
+    /// Where the children of an aggregate start in `children`.
+    first_child: usize,
+    /// The number of direct children, counting keys and values of maps separately.
+    child_count: usize,
+}
+
+/// An aggregate that's still being scanned.
+struct Open {
+    node: usize,
+    /// The children still missing, `None` for streamed aggregates.
+    missing: Option<usize>,
+}
+
+impl<'de> RespDocument<'de> {
+    /// Scans the first value in `input`, failing if it's incomplete or malformed.
+    ///
+    /// Anything after the value is ignored, see [`RespDocument::len_bytes`].
+    pub fn new(input: &'de [u8]) -> Result<Self> {
+        let mut deserializer = Deserializer::new(input);
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut parents = Vec::new();
+        let mut open: Vec<Open> = Vec::new();
+        loop {
+            let start = input.len() - deserializer.remaining();
+            let header = deserializer.skip_header()?;
+            let end = input.len() - deserializer.remaining();
+            let (kind, missing) = match header {
+                Header::End => {
+                    let closed = open.pop().ok_or(Error::UnrecognizedStart)?;
+                    let node = &mut nodes[closed.node];
+                    if closed.missing.is_some()
+                        || matches!(node.kind, RespDataKind::Map | RespDataKind::Attributes)
+                            && node.child_count % 2 == 1
+                    {
+                        return Err(Error::UnrecognizedStart);
+                    }
+                    node.end = end;
+                    if close_complete(&mut nodes, &mut open, end) {
+                        break;
+                    }
+                    continue;
+                }
+                Header::Scalar(kind, _) => (kind, Some(0)),
+                Header::Aggregate(kind, Some(length)) => (kind, Some(kind.children(length)?)),
+                Header::Aggregate(kind, None) => (kind, None),
+            };
+            let index = nodes.len();
+            if let Some(parent) = open.last_mut() {
+                if let Some(missing) = &mut parent.missing {
+                    *missing -= 1;
+                }
+                nodes[parent.node].child_count += 1;
+                parents.push(parent.node);
+            } else {
+                parents.push(usize::MAX);
+            }
+            nodes.push(Node {
+                kind,
+                start,
+                end,
+                first_child: 0,
+                child_count: 0,
+            });
+            open.push(Open {
+                node: index,
+                missing,
+            });
+            if close_complete(&mut nodes, &mut open, end) {
+                break;
+            }
+        }
+
+        // Group the children of each aggregate, in order
+        let mut first_child = 0;
+        for node in &mut nodes {
+            node.first_child = first_child;
+            first_child += node.child_count;
+        }
+        let mut children = vec![0; first_child];
+        let mut filled: Vec<usize> = nodes.iter().map(|node| node.first_child).collect();
+        for (index, &parent) in parents.iter().enumerate().skip(1) {
+            children[filled[parent]] = index;
+            filled[parent] += 1;
+        }
+        Ok(Self {
+            input,
+            nodes,
+            children,
+        })
+    }
+
+    /// The whole value.
+    #[must_use]
+    pub fn root(&self) -> DocumentNode<'_, 'de> {
+        DocumentNode {
+            document: self,
+            index: 0,
+        }
+    }
+
+    /// The number of input bytes taken by the value.
+    #[must_use]
+    pub fn len_bytes(&self) -> usize {
+        self.nodes[0].end
+    }
+}
+
+/// Closes the open values that have all of their children, which end at `end`.
+/// Returns whether the whole value is complete.
+fn close_complete(nodes: &mut [Node], open: &mut Vec<Open>, end: usize) -> bool {
+    while let Some(Open {
+        node,
+        missing: Some(0),
+    }) = open.last()
+    {
+        nodes[*node].end = end;
+        open.pop();
+    }
+    open.is_empty()
+}
+
+/// A value inside a [`RespDocument`].
+#[derive(Debug, Clone, Copy)]
+pub struct DocumentNode<'a, 'de> {
+    document: &'a RespDocument<'de>,
+    index: usize,
+}
+
+impl<'a, 'de> DocumentNode<'a, 'de> {
+    fn node(&self) -> &'a Node {
+        &self.document.nodes[self.index]
+    }
+
+    fn child(&self, position: usize) -> Option<Self> {
+        let node = self.node();
+        if position >= node.child_count {
+            return None;
+        }
+        Some(Self {
+            document: self.document,
+            index: self.document.children[node.first_child + position],
+        })
+    }
+
+    const fn is_map(kind: RespDataKind) -> bool {
+        matches!(kind, RespDataKind::Map | RespDataKind::Attributes)
+    }
+
+    /// The kind of the value. RESP2 nulls are [`RespDataKind::Null`].
+    #[must_use]
+    pub fn kind(&self) -> RespDataKind {
+        self.node().kind
+    }
+
+    /// The number of elements of an array, set or push, or the number of entries of a map
+    /// or attributes. Other values have none.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let node = self.node();
+        if Self::is_map(node.kind) {
+            node.child_count / 2
+        } else {
+            node.child_count
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Element `index` of an array, set or push.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Self> {
+        if Self::is_map(self.kind()) {
+            return None;
+        }
+        self.child(index)
+    }
+
+    /// The key and value of entry `index` of a map or attributes.
+    #[must_use]
+    pub fn entry(&self, index: usize) -> Option<(Self, Self)> {
+        if !Self::is_map(self.kind()) {
+            return None;
+        }
+        let position = index.checked_mul(2)?;
+        Some((self.child(position)?, self.child(position + 1)?))
+    }
+
+    /// The value of the first entry of a map (or attributes) with a matching key, of any
+    /// string kind. Only the keys are looked at, not the values.
+    #[must_use]
+    pub fn get_key(&self, key: &str) -> Option<Self> {
+        (0..self.len()).find_map(|index| {
+            let (k, v) = self.entry(index)?;
+            let mut deserializer = Deserializer::new(k.as_bytes());
+            match deserializer.next_header() {
+                Ok(Header::Scalar(_, data)) if *data == *key.as_bytes() => Some(v),
+                _ => None,
+            }
+        })
+    }
+
+    /// The elements of an array, set or push, or the keys and values of a map one after
+    /// the other.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Self> + use<'a, 'de> {
+        let node = self.node();
+        let document = self.document;
+        document.children[node.first_child..node.first_child + node.child_count]
+            .iter()
+            .map(move |&index| Self { document, index })
+    }
+
+    /// The raw bytes of the value, including everything nested in it.
+    #[must_use]
+    pub fn as_bytes(&self) -> &'de [u8] {
+        let node = self.node();
+        &self.document.input[node.start..node.end]
+    }
+
+    /// Deserializes only this value (and what's nested in it).
+    pub fn deserialize<T>(&self) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        crate::from_bytes(self.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    #[test]
+    fn test_document() {
+        let raw = b"*4\r\n*0\r\n*?\r\n:1\r\n*1\r\n$-1\r\n.\r\n~2\r\n+a\r\n+b\r\n#t\r\n:5\r\n";
+        let document = RespDocument::new(raw).unwrap();
+        assert_eq!(
+            document.len_bytes(),
+            raw.len() - 4,
+            "The trailing value is ignored"
+        );
+        let root = document.root();
+        assert_eq!((root.kind(), root.len()), (RespDataKind::Array, 4));
+        assert!(root.get(0).unwrap().is_empty());
+        let streamed = root.get(1).unwrap();
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(streamed.as_bytes(), b"*?\r\n:1\r\n*1\r\n$-1\r\n.\r\n");
+        assert_eq!(
+            streamed.get(1).unwrap().get(0).unwrap().kind(),
+            RespDataKind::Null
+        );
+        assert_eq!(
+            root.get(2).unwrap().deserialize::<Vec<String>>().unwrap(),
+            ["a", "b"]
+        );
+        assert!(root.get(3).unwrap().deserialize::<bool>().unwrap());
+        assert!(root.get(4).is_none());
+        let kinds: Vec<_> = root.iter().map(|node| node.kind()).collect();
+        assert_eq!(
+            kinds,
+            [
+                RespDataKind::Array,
+                RespDataKind::Array,
+                RespDataKind::Set,
+                RespDataKind::Boolean
+            ]
+        );
+        let all: Value = root.deserialize().unwrap();
+        assert_eq!(all, crate::from_bytes::<Value>(raw).unwrap());
+        for end in 0..raw.len() - 4 {
+            assert!(
+                matches!(RespDocument::new(&raw[..end]), Err(Error::UnexpectedEnd)),
+                "{end}"
+            );
+        }
+        assert!(RespDocument::new(b"*1\r\n?\r\n").is_err());
+        assert!(RespDocument::new(b"*1\r\n.\r\n").is_err());
+        assert!(RespDocument::new(b".\r\n").is_err());
+    }
+
+    #[test]
+    fn test_map() {
+        let raw = b"%?\r\n$?\r\n;1\r\na\r\n;0\r\n:1\r\n:2\r\n%1\r\n+x\r\n=7\r\ntxt:abc\r\n.\r\n";
+        let document = RespDocument::new(raw).unwrap();
+        let root = document.root();
+        assert_eq!(root.len(), 2);
+        assert!(root.get(0).is_none(), "Maps have entries");
+        assert_eq!(root.get_key("a").unwrap().deserialize::<u8>().unwrap(), 1);
+        assert_eq!(root.get_key("2").unwrap().kind(), RespDataKind::Map);
+        assert!(root.get_key("b").is_none());
+        let (key, value) = root.entry(1).unwrap();
+        assert_eq!(key.deserialize::<u8>().unwrap(), 2);
+        assert_eq!(
+            value.get_key("x").unwrap().deserialize::<&str>().unwrap(),
+            "txt:abc"
+        );
+        assert!(root.entry(2).is_none());
+        assert_eq!(root.iter().len(), 4);
+        assert!(
+            RespDocument::new(b"%?\r\n+a\r\n.\r\n").is_err(),
+            "A key without a value"
+        );
+    }
+}
@@ -1,19 +1,37 @@
-use std::{str::Utf8Error, string::FromUtf8Error};
+use std::{str::Utf8Error, string::FromUtf8Error, sync::Arc};
 
 #[derive(Debug, Clone)]
 pub enum Error {
     SerializeError(String),
     DeserializeError(String),
     UnexpectedEnd,
-    UnexpectedByte { expected: String, found: char },
+    UnexpectedByte {
+        expected: String,
+        found: char,
+    },
     UnrecognizedStart,
     InvalidUtf8,
     ExpectedLength,
+    /// The input is nested deeper than the deserializer allows, see `Deserializer`.
+    RecursionLimitExceeded,
+    /// A line read from a reader (a header or a simple value) is longer than 64 KiB.
+    LineTooLong,
+    /// A value received by `RespCodec` is longer than its maximum frame length.
+    FrameTooLong,
+    /// Reading or writing failed. Kept in an `Arc` so the error can still be cloned.
+    Io(Arc<std::io::Error>),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -31,6 +49,10 @@ impl std::fmt::Display for Error {
             Error::UnrecognizedStart => write!(f, "Unrecognized start of RESP data"),
             Error::InvalidUtf8 => write!(f, "Invalid UTF-8 sequence in RESP data"),
             Error::ExpectedLength => write!(f, "Expected a length for following items"),
+            Error::RecursionLimitExceeded => write!(f, "Recursion limit exceeded"),
+            Error::LineTooLong => write!(f, "Line too long in RESP data"),
+            Error::FrameTooLong => write!(f, "RESP value longer than the maximum frame length"),
+            Error::Io(err) => write!(f, "IO error: {err}"),
         }
     }
 }
@@ -64,3 +86,14 @@ impl From<Utf8Error> for Error {
         Self::InvalidUtf8
     }
 }
+
+/// Running out of input is reported the same way for readers and for slices.
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        if err.kind() == std::io::ErrorKind::UnexpectedEof {
+            Self::UnexpectedEnd
+        } else {
+            Self::Io(Arc::new(err))
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    Deserializer, Error, RespDataKind, Result,
+    de::{Header, streamed_map_error},
+};
+use std::borrow::Cow;
+
+/// A single parsing event, see [`Events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<'de> {
+    /// The start of an aggregate (array, set, push, map or attributes) with the length in
+    /// its header. For maps and attributes that's the number of pairs, so twice as many
+    /// values follow. Streamed aggregates (`*?\r\n`) have no length.
+    StartAggregate(RespDataKind, Option<usize>),
+    /// A complete non-aggregate value and its raw data, without the prefix, length and CRLFs.
+    ///
+    /// Nulls, including the RESP2 null bulk string and null array, are reported with
+    /// [`RespDataKind::Null`] and no data. The data is borrowed from the input, except for
+    /// streamed strings, whose chunks are joined.
+    Scalar(RespDataKind, Cow<'de, [u8]>),
+    /// The end of the most recently started aggregate.
+    EndAggregate,
+}
+
+/// An iterator of parsing [`Event`]s over RESP input, without building any values.
+///
+/// Useful for very large replies, where only some aggregate information is needed.
+/// Iterates over every value in the input, and stops after the first error.
+///
+/// ```
+/// # use rediserde::{Event, Events, RespDataKind};
+/// let input = b"*3\r\n:1\r\n*1\r\n:2\r\n:3\r\n";
+/// let mut sum = 0;
+/// for event in Events::new(input) {
+///     if let Event::Scalar(RespDataKind::Integer, data) = event.unwrap() {
+///         sum += str::from_utf8(&data).unwrap().parse::<i64>().unwrap();
+///     }
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+pub struct Events<'de> {
+    de: Deserializer<'de>,
+    /// The currently open aggregates
+    pending: Vec<Open>,
+    failed: bool,
+}
+
+/// An aggregate that [`Events`] started but didn't end yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Open {
+    /// An aggregate with a length, and the number of values left in it
+    Known(usize),
+    /// A streamed aggregate. For maps and attributes, `key` tells whether a key is still
+    /// waiting for its value
+    Streamed { map: bool, key: bool },
+}
+
+impl<'de> Events<'de> {
+    #[must_use]
+    pub const fn new(input: &'de [u8]) -> Self {
+        Self {
+            de: Deserializer::new(input),
+            pending: Vec::new(),
+            failed: false,
+        }
+    }
+
+    fn next_event(&mut self) -> Result<Event<'de>> {
+        let header = self.de.next_header()?;
+        if let Header::End = header {
+            return match self.pending.pop() {
+                Some(Open::Streamed { key: false, .. }) => Ok(Event::EndAggregate),
+                Some(Open::Streamed { key: true, .. }) => Err(streamed_map_error()),
+                _ => Err(Error::UnrecognizedStart),
+            };
+        }
+        match self.pending.last_mut() {
+            Some(Open::Known(pending)) => *pending -= 1,
+            Some(Open::Streamed { map: true, key }) => *key = !*key,
+            _ => {}
+        }
+        match header {
+            Header::Scalar(kind, data) => Ok(Event::Scalar(kind, data)),
+            Header::Aggregate(kind, length) => {
+                self.pending.push(match length {
+                    Some(length) => Open::Known(kind.children(length)?),
+                    None => Open::Streamed {
+                        map: matches!(kind, RespDataKind::Map | RespDataKind::Attributes),
+                        key: false,
+                    },
+                });
+                Ok(Event::StartAggregate(kind, length))
+            }
+            Header::End => unreachable!("Handled above"),
+        }
+    }
+}
+
+impl<'de> Iterator for Events<'de> {
+    type Item = Result<Event<'de>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        if self.pending.last() == Some(&Open::Known(0)) {
+            self.pending.pop();
+            return Some(Ok(Event::EndAggregate));
+        }
+        if self.pending.is_empty() && self.de.is_empty() {
+            return None;
+        }
+        let event = self.next_event();
+        self.failed = event.is_err();
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events() {
+        let input = b"%2\r\n+a\r\n*0\r\n$1\r\nb\r\n~2\r\n#t\r\n$-1\r\n:1\r\n";
+        let events: Vec<Event> = Events::new(input).collect::<Result<_>>().unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event::StartAggregate(RespDataKind::Map, Some(2)),
+                Event::Scalar(RespDataKind::SimpleString, Cow::Borrowed(b"a")),
+                Event::StartAggregate(RespDataKind::Array, Some(0)),
+                Event::EndAggregate,
+                Event::Scalar(RespDataKind::BulkString, Cow::Borrowed(b"b")),
+                Event::StartAggregate(RespDataKind::Set, Some(2)),
+                Event::Scalar(RespDataKind::Boolean, Cow::Borrowed(b"t")),
+                Event::Scalar(RespDataKind::Null, Cow::Borrowed(b"")),
+                Event::EndAggregate,
+                Event::EndAggregate,
+                Event::Scalar(RespDataKind::Integer, Cow::Borrowed(b"1")),
+            ]
+        );
+        let mut events = Events::new(b"*2\r\n:1\r\n");
+        assert!(matches!(events.next(), Some(Ok(Event::StartAggregate(..)))));
+        assert!(matches!(events.next(), Some(Ok(Event::Scalar(..)))));
+        assert!(matches!(events.next(), Some(Err(Error::UnexpectedEnd))));
+        assert!(events.next().is_none(), "Stops after an error");
+    }
+
+    #[test]
+    fn test_streamed() {
+        let input = b"*?\r\n$?\r\n;2\r\nab\r\n;1\r\nc\r\n;0\r\n%?\r\n.\r\n.\r\n";
+        let events: Vec<Event> = Events::new(input).collect::<Result<_>>().unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event::StartAggregate(RespDataKind::Array, None),
+                Event::Scalar(RespDataKind::BulkString, Cow::Owned(b"abc".to_vec())),
+                Event::StartAggregate(RespDataKind::Map, None),
+                Event::EndAggregate,
+                Event::EndAggregate,
+            ]
+        );
+        let mut events = Events::new(b"*1\r\n.\r\n");
+        assert!(matches!(events.next(), Some(Ok(Event::StartAggregate(..)))));
+        assert!(matches!(events.next(), Some(Err(Error::UnrecognizedStart))));
+
+        let events: Vec<Event> = Events::new(b"%?\r\n+a\r\n*?\r\n.\r\n.\r\n")
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(events.len(), 5);
+        let events: Vec<Result<Event>> = Events::new(b"%?\r\n+a\r\n.\r\n").collect();
+        assert!(matches!(
+            &events[..],
+            [Ok(Event::StartAggregate(RespDataKind::Map, None)), Ok(Event::Scalar(..)), Err(Error::DeserializeError(message))]
+                if message == "A streamed map ended between a key and its value"
+        ));
+    }
+}
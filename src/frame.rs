@@ -0,0 +1,367 @@
+//! Parsing and writing RESP values without going through serde.
+//!
+//! Frames are represented as [`Value`]s, which keep the exact RESP kind of every value.
+
+use crate::big_number::check_digits;
+use crate::value::{join_verbatim, split_verbatim};
+use crate::{BigNumber, CRLF, Deserializer, Error, RespDataKind, Result, Value, de::Header};
+
+/// Parses one complete RESP value from the start of `input`.
+///
+/// Returns the value and the number of bytes it took, any bytes after that are left untouched.
+///
+/// ```
+/// # use rediserde::{Value, parse_frame};
+/// let input = b"*2\r\n+OK\r\n:1\r\n$5\r\nnext!\r\n";
+/// let (frame, consumed) = parse_frame(input).unwrap();
+/// assert_eq!(frame, Value::Array(vec![Value::SimpleString("OK".to_string()), Value::Integer(1)]));
+/// assert_eq!(consumed, 13);
+/// let (next, _) = parse_frame(&input[consumed..]).unwrap();
+/// assert_eq!(next, Value::BulkString(b"next!".to_vec()));
+/// ```
+pub fn parse_frame(input: &[u8]) -> Result<(Value, usize)> {
+    let mut deserializer = Deserializer::new(input);
+    let frame = parse_value(&mut deserializer)?;
+    Ok((frame, input.len() - deserializer.remaining()))
+}
+
+/// Writes a RESP value to the end of `output`.
+///
+/// The output is identical to serializing the value with [`crate::to_bytes`].
+///
+/// ```
+/// # use rediserde::{Value, write_frame};
+/// let mut output = Vec::new();
+/// write_frame(&Value::Set(vec![Value::Boolean(true)]), &mut output).unwrap();
+/// assert_eq!(output, b"~1\r\n#t\r\n");
+/// ```
+pub fn write_frame(frame: &Value, output: &mut Vec<u8>) -> Result<()> {
+    match frame {
+        Value::SimpleString(s) | Value::SimpleError(s) => {
+            write_simple(frame.kind(), s.as_bytes(), output)?;
+        }
+        Value::BigNumber(n) => write_simple(frame.kind(), n.as_str().as_bytes(), output)?,
+        Value::Integer(i) => write_simple(frame.kind(), i.to_string().as_bytes(), output)?,
+        Value::Float(f) => write_simple(frame.kind(), format_float(*f).as_bytes(), output)?,
+        Value::Boolean(b) => write_simple(frame.kind(), if *b { b"t" } else { b"f" }, output)?,
+        Value::Null => write_simple(frame.kind(), b"", output)?,
+        Value::BulkString(b) => write_bulk(frame.kind(), b, output),
+        Value::BulkError(s) => write_bulk(frame.kind(), s.as_bytes(), output),
+        Value::VerbatimString { encoding, text } => {
+            let data = join_verbatim::<Error>(encoding, text)?;
+            write_bulk(frame.kind(), data.as_bytes(), output);
+        }
+        Value::Array(items) | Value::Set(items) | Value::Push(items) => {
+            write_header(frame.kind(), items.len(), output);
+            for item in items {
+                write_frame(item, output)?;
+            }
+        }
+        Value::Map(entries) | Value::Attributes(entries) => {
+            write_header(frame.kind(), entries.len(), output);
+            for (key, value) in entries {
+                write_frame(key, output)?;
+                write_frame(value, output)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_value(deserializer: &mut Deserializer) -> Result<Value> {
+    let header = deserializer.next_header()?;
+    parse_rest(deserializer, header)
+}
+
+/// Parses the rest of a value after its header.
+fn parse_rest(deserializer: &mut Deserializer, header: Header) -> Result<Value> {
+    let (kind, data) = match header {
+        Header::Scalar(kind, data) => (kind, data),
+        Header::Aggregate(kind, Some(length)) => {
+            let children = kind.children(length)?;
+            let items = deserializer.nested(|deserializer| {
+                let mut items = Vec::new();
+                for _ in 0..children {
+                    items.push(parse_value(deserializer)?);
+                }
+                Ok(items)
+            })?;
+            return Ok(aggregate(kind, items));
+        }
+        Header::Aggregate(kind, None) => {
+            let items = deserializer.nested(|deserializer| {
+                let mut items = Vec::new();
+                loop {
+                    match deserializer.next_header()? {
+                        Header::End => return Ok(items),
+                        header => items.push(parse_rest(deserializer, header)?),
+                    }
+                }
+            })?;
+            if matches!(kind, RespDataKind::Map | RespDataKind::Attributes) && items.len() % 2 == 1
+            {
+                return Err(crate::de::streamed_map_error());
+            }
+            return Ok(aggregate(kind, items));
+        }
+        Header::End => return Err(Error::UnrecognizedStart),
+    };
+    let data = &*data;
+    let frame = match kind {
+        RespDataKind::SimpleString => Value::SimpleString(str::from_utf8(data)?.to_string()),
+        RespDataKind::SimpleError => Value::SimpleError(str::from_utf8(data)?.to_string()),
+        RespDataKind::Integer => Value::Integer(parse_number(data)?),
+        RespDataKind::Float => Value::Float(parse_number(data)?),
+        RespDataKind::BigNumber => Value::BigNumber(BigNumber::new(str::from_utf8(data)?)?),
+        RespDataKind::Boolean => Value::Boolean(data == b"t"),
+        RespDataKind::BulkString => Value::BulkString(data.to_vec()),
+        RespDataKind::BulkError => Value::BulkError(str::from_utf8(data)?.to_string()),
+        RespDataKind::VerbatimString => {
+            let (encoding, text) = split_verbatim::<Error>(str::from_utf8(data)?)?;
+            Value::VerbatimString {
+                encoding: encoding.to_string(),
+                text: text.to_string(),
+            }
+        }
+        RespDataKind::Null => Value::Null,
+        RespDataKind::Array
+        | RespDataKind::Set
+        | RespDataKind::Push
+        | RespDataKind::Map
+        | RespDataKind::Attributes => unreachable!("Aggregates are handled above"),
+    };
+    Ok(frame)
+}
+
+/// Checks the data of a scalar by the same rules [`parse_frame`] reads it with, without
+/// building the value.
+pub(crate) fn check_scalar(kind: RespDataKind, data: &[u8]) -> Result<()> {
+    match kind {
+        RespDataKind::SimpleString | RespDataKind::SimpleError | RespDataKind::BulkError => {
+            str::from_utf8(data)?;
+        }
+        RespDataKind::BigNumber => check_digits(str::from_utf8(data)?)?,
+        RespDataKind::Integer => {
+            parse_number::<i64>(data)?;
+        }
+        RespDataKind::Float => {
+            parse_number::<f64>(data)?;
+        }
+        RespDataKind::VerbatimString => {
+            split_verbatim::<Error>(str::from_utf8(data)?)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Splits the items of an aggregate into pairs if it's a map.
+fn aggregate(kind: RespDataKind, items: Vec<Value>) -> Value {
+    let pairs = |items: Vec<Value>| {
+        let mut pairs = Vec::with_capacity(items.len() / 2);
+        let mut items = items.into_iter();
+        while let (Some(key), Some(value)) = (items.next(), items.next()) {
+            pairs.push((key, value));
+        }
+        pairs
+    };
+    match kind {
+        RespDataKind::Set => Value::Set(items),
+        RespDataKind::Push => Value::Push(items),
+        RespDataKind::Map => Value::Map(pairs(items)),
+        RespDataKind::Attributes => Value::Attributes(pairs(items)),
+        _ => Value::Array(items),
+    }
+}
+
+pub(crate) fn parse_number<N>(data: &[u8]) -> Result<N>
+where
+    N: std::str::FromStr,
+{
+    let value_str = str::from_utf8(data)?;
+    value_str.parse::<N>().map_err(|_| Error::UnexpectedByte {
+        expected: "A valid number string".to_string(),
+        found: value_str.chars().next().unwrap_or_default(),
+    })
+}
+
+/// Formats a RESP double: `inf`, `-inf` and `nan` for the special values, otherwise the
+/// shortest digits that round-trip, in exponent notation when it's shorter (e.g. `2e20`).
+pub(crate) fn format_float<F>(v: F) -> String
+where
+    F: std::fmt::Display + std::fmt::LowerExp + Into<f64> + Copy,
+{
+    let wide: f64 = v.into();
+    if wide.is_nan() {
+        return "nan".to_string();
+    }
+    if wide.is_infinite() {
+        return if wide.is_sign_negative() {
+            "-inf"
+        } else {
+            "inf"
+        }
+        .to_string();
+    }
+    let plain = v.to_string();
+    let exponent = format!("{v:e}");
+    if exponent.len() < plain.len() {
+        exponent
+    } else {
+        plain
+    }
+}
+
+/// Parses a 128-bit integer, reporting values that don't fit the type as such.
+pub(crate) fn parse_wide_integer<N>(data: &[u8]) -> Result<N>
+where
+    N: std::str::FromStr<Err = std::num::ParseIntError>,
+{
+    let value_str = str::from_utf8(data)?;
+    value_str.parse::<N>().map_err(|err| match err.kind() {
+        std::num::IntErrorKind::PosOverflow | std::num::IntErrorKind::NegOverflow => {
+            Error::DeserializeError(format!(
+                "{value_str} is out of range for {}",
+                std::any::type_name::<N>()
+            ))
+        }
+        _ => Error::UnexpectedByte {
+            expected: "A valid integer string".to_string(),
+            found: value_str.chars().next().unwrap_or_default(),
+        },
+    })
+}
+
+/// <prefix><data>\r\n
+fn write_simple(kind: RespDataKind, data: &[u8], output: &mut Vec<u8>) -> Result<()> {
+    if data.contains(&b'\r') || data.contains(&b'\n') {
+        return Err(Error::SerializeError(format!(
+            "A {kind:?} cannot contain CR or LF characters"
+        )));
+    }
+    output.push(kind.to_prefix_bytes());
+    output.extend_from_slice(data);
+    output.extend_from_slice(CRLF);
+    Ok(())
+}
+
+/// <prefix><length>\r\n<data>\r\n
+fn write_bulk(kind: RespDataKind, data: &[u8], output: &mut Vec<u8>) {
+    write_header(kind, data.len(), output);
+    output.extend_from_slice(data);
+    output.extend_from_slice(CRLF);
+}
+
+/// <prefix><length>\r\n
+fn write_header(kind: RespDataKind, length: usize, output: &mut Vec<u8>) {
+    output.push(kind.to_prefix_bytes());
+    output.extend_from_slice(length.to_string().as_bytes());
+    output.extend_from_slice(CRLF);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_bytes, to_bytes};
+
+    const RAW: &[u8] = b"*8\r\n+OK\r\n-ERR\r\n:-1\r\n,1.5\r\n(123456789012345678901234\r\n\
+        $2\r\n\xff\x00\r\n=7\r\ntxt:abc\r\n%2\r\n#t\r\n_\r\n!3\r\nbad\r\n|1\r\n:1\r\n>1\r\n~0\r\n";
+
+    #[test]
+    fn test_parse() {
+        let (frame, consumed) = parse_frame(RAW).unwrap();
+        assert_eq!(consumed, RAW.len());
+        assert_eq!(frame, from_bytes::<Value>(RAW).unwrap(), "Same as serde");
+        let Value::Array(items) = &frame else {
+            panic!("Expected an array")
+        };
+        assert_eq!(items[5], Value::BulkString(vec![0xff, 0x00]));
+        assert_eq!(
+            items[7],
+            Value::Map(vec![
+                (Value::Boolean(true), Value::Null),
+                (
+                    Value::BulkError("bad".to_string()),
+                    Value::Attributes(vec![(
+                        Value::Integer(1),
+                        Value::Push(vec![Value::Set(vec![])])
+                    )])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let (frame, _) = parse_frame(RAW).unwrap();
+        let mut output = b"prefix".to_vec();
+        write_frame(&frame, &mut output).unwrap();
+        assert_eq!(&output[..6], b"prefix");
+        assert_eq!(&output[6..], RAW);
+        assert_eq!(&output[6..], to_bytes(&frame).unwrap(), "Same as serde");
+
+        let raw = b"*3\r\n,inf\r\n,-2.5e-30\r\n,nan\r\n";
+        let (frame, _) = parse_frame(raw).unwrap();
+        let mut output = Vec::new();
+        write_frame(&frame, &mut output).unwrap();
+        assert_eq!(output, raw);
+    }
+
+    #[test]
+    fn test_streamed() {
+        let raw = b"%?\r\n+a\r\n$?\r\n;1\r\nb\r\n;0\r\n+c\r\n*?\r\n.\r\n.\r\n:1\r\n";
+        let (frame, consumed) = parse_frame(raw).unwrap();
+        assert_eq!(consumed, raw.len() - 4);
+        assert_eq!(
+            frame,
+            Value::Map(vec![
+                (
+                    Value::SimpleString("a".to_string()),
+                    Value::BulkString(b"b".to_vec())
+                ),
+                (Value::SimpleString("c".to_string()), Value::Array(vec![])),
+            ])
+        );
+        assert!(parse_frame(b"%?\r\n+a\r\n.\r\n").is_err());
+        assert!(matches!(
+            parse_frame(b"*?\r\n:1\r\n"),
+            Err(Error::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn test_nesting() {
+        let nested = |header: &[u8], depth: usize| [&header.repeat(depth)[..], b":1\r\n"].concat();
+        let deepest = nested(b"*1\r\n", 128);
+        assert_eq!(parse_frame(&deepest).unwrap().1, deepest.len());
+        assert!(from_bytes::<Value>(&deepest).is_ok());
+        for too_deep in [nested(b"*1\r\n", 200_000), nested(b"*?\r\n", 129)] {
+            assert!(matches!(
+                parse_frame(&too_deep),
+                Err(Error::RecursionLimitExceeded)
+            ));
+            assert!(matches!(
+                from_bytes::<Value>(&too_deep),
+                Err(Error::RecursionLimitExceeded)
+            ));
+            assert!(matches!(
+                from_bytes::<crate::ValueRef>(&too_deep),
+                Err(Error::RecursionLimitExceeded)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            parse_frame(b"*2\r\n:1\r\n"),
+            Err(Error::UnexpectedEnd)
+        ));
+        assert!(parse_frame(b":abc\r\n").is_err());
+        assert!(parse_frame(b"#x\r\n").is_err());
+        assert!(parse_frame(b"=3\r\nabc\r\n").is_err());
+        assert!(parse_frame(b"?\r\n").is_err());
+        let frame = Value::SimpleString("a\nb".to_string());
+        assert!(write_frame(&frame, &mut Vec::new()).is_err());
+    }
+}
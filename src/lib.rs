@@ -45,15 +45,50 @@
 //! ```
 //!
 
+mod big_number;
+#[cfg(feature = "tokio")]
+mod codec;
 mod de;
+mod decode;
+mod document;
 mod error;
+mod event;
+mod frame;
+#[cfg(feature = "futures")]
+pub mod futures;
+mod macros;
+mod raw;
+mod read;
 mod resp;
 mod ser;
+#[cfg(feature = "bytes")]
+pub mod shared_bytes;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+mod value;
 
-pub use de::{Deserializer, from_bytes, from_str};
+pub use big_number::BigNumber;
+#[cfg(feature = "tokio")]
+pub use codec::RespCodec;
+pub use de::{ArrayIter, Deserializer, StreamDeserializer, from_bytes, from_str};
+pub use decode::{Decoded, Decoder, frame_len, validate};
+pub use document::{DocumentNode, RespDocument};
 pub use error::{Error, Result};
+pub use event::{Event, Events};
+pub use frame::{parse_frame, write_frame};
+pub use raw::RawResp;
+pub use read::{BulkReader, ReaderArrayIter, from_reader, iter_array_from_reader, read_bulk};
 pub use resp::RespDataKind;
-pub use ser::{Serializer, to_bytes, to_string};
+pub use ser::{Serializer, to_bytes, to_string, to_writer};
+#[cfg(feature = "bytes")]
+pub use shared_bytes::{from_shared_bytes, to_bytes_mut};
+pub use value::{Value, ValueIndex, ValueRef, from_value, to_value};
 
 pub const CRLF: &[u8] = b"\r\n";
 pub const CRLF_STR: &str = "\r\n";
+/// The length of streamed strings and aggregates, which mark their own end, e.g. `*?\r\n`.
+pub(crate) const STREAMED: u8 = b'?';
+/// The end of a streamed aggregate.
+pub(crate) const STREAMED_END: &[u8] = b".\r\n";
+/// The prefix of each chunk in a streamed string, e.g. `;4\r\nHell\r\n`.
+pub(crate) const STREAMED_CHUNK: u8 = b';';
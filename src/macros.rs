@@ -0,0 +1,345 @@
+/// Builds encoded RESP data (as `Vec<u8>`) from a readable description of the value.
+///
+/// Plain Rust expressions are encoded the same way [`crate::to_bytes`] encodes them, so strings
+/// become bulk strings and integers become RESP integers. This includes map keys, so the
+/// simple string keys a struct is serialized with are written as `simple "name"`. Other kinds
+/// are written by name:
+///
+/// | Syntax                    | RESP kind         |
+/// |---------------------------|-------------------|
+/// | `simple <expr>`           | Simple string     |
+/// | `error <expr>`            | Simple error      |
+/// | `bulk <expr>`             | Bulk string, from anything that is `AsRef<[u8]>` |
+/// | `bulk_error <expr>`       | Bulk error        |
+/// | `verbatim <expr>`         | Verbatim string, with the `txt` encoding |
+/// | `verbatim "mkd" <expr>`   | Verbatim string, with the given encoding |
+/// | `big <expr>`              | Big number        |
+/// | `null`                    | Null              |
+/// | `[...]` or `array [...]`  | Array             |
+/// | `set [...]`               | Set               |
+/// | `push [...]`              | Push              |
+/// | `map { k => v, ... }`     | Map               |
+/// | `attributes { k => v, ... }` | Attributes     |
+///
+/// ```
+/// # use rediserde::resp;
+/// let encoded = resp! { map { simple "name" => simple "Alice", simple "age" => 30 } };
+/// assert_eq!(encoded, b"%2\r\n+name\r\n+Alice\r\n+age\r\n:30\r\n");
+///
+/// let encoded = resp! { map { "name" => verbatim "mkd" "*Alice*" } };
+/// assert_eq!(encoded, b"%1\r\n$4\r\nname\r\n=11\r\nmkd:*Alice*\r\n");
+///
+/// let encoded = resp!([simple "OK", set [1, true], null]);
+/// assert_eq!(encoded, b"*3\r\n+OK\r\n~2\r\n:1\r\n#t\r\n_\r\n");
+/// ```
+///
+/// # Panics
+///
+/// If a value cannot be encoded, for example a simple string that contains CR or LF.
+#[macro_export]
+macro_rules! resp {
+    // A single value, builds a `Value`.
+    (@value null) => {
+        $crate::Value::Null
+    };
+    (@value simple $e:expr) => {
+        $crate::Value::SimpleString(::std::string::ToString::to_string(&$e))
+    };
+    (@value error $e:expr) => {
+        $crate::Value::SimpleError(::std::string::ToString::to_string(&$e))
+    };
+    (@value bulk $e:expr) => {
+        $crate::Value::BulkString(::std::convert::AsRef::<[u8]>::as_ref(&$e).to_vec())
+    };
+    (@value bulk_error $e:expr) => {
+        $crate::Value::BulkError(::std::string::ToString::to_string(&$e))
+    };
+    (@value verbatim $e:expr) => {
+        $crate::Value::VerbatimString {
+            encoding: ::std::string::ToString::to_string("txt"),
+            text: ::std::string::ToString::to_string(&$e),
+        }
+    };
+    (@value verbatim $encoding:literal $e:expr) => {
+        $crate::Value::VerbatimString {
+            encoding: ::std::string::ToString::to_string($encoding),
+            text: ::std::string::ToString::to_string(&$e),
+        }
+    };
+    (@value big $e:expr) => {
+        $crate::Value::BigNumber(
+            $crate::BigNumber::new(::std::string::ToString::to_string(&$e))
+                .expect("Big number in resp! is not a valid number"),
+        )
+    };
+    (@value [$($items:tt)*]) => {
+        $crate::Value::Array($crate::resp!(@seq [] [] $($items)*))
+    };
+    (@value array [$($items:tt)*]) => {
+        $crate::Value::Array($crate::resp!(@seq [] [] $($items)*))
+    };
+    (@value set [$($items:tt)*]) => {
+        $crate::Value::Set($crate::resp!(@seq [] [] $($items)*))
+    };
+    (@value push [$($items:tt)*]) => {
+        $crate::Value::Push($crate::resp!(@seq [] [] $($items)*))
+    };
+    (@value map {$($entries:tt)*}) => {
+        $crate::Value::Map($crate::resp!(@map [] [] $($entries)*))
+    };
+    (@value attributes {$($entries:tt)*}) => {
+        $crate::Value::Attributes($crate::resp!(@map [] [] $($entries)*))
+    };
+    (@value $e:expr) => {
+        $crate::to_value(&$e).expect("Value in resp! cannot be serialized")
+    };
+
+    // Items of a sequence. Runs of short items are split off in a single step, and longer
+    // items a few tokens at a time, so the recursion depth barely grows with the length of
+    // the sequence (only with how deeply it's nested).
+    //
+    // All the remaining items are single tokens (like `1`, `"a"` or `[...]`), or all are a
+    // token and a literal (like `-1` or `simple "a"`). The second token has to be a literal
+    // because a plain `tt` would also match the comma after a single token item.
+    (@seq [$($done:expr,)*] [] $($a:tt),* $(,)?) => {
+        ::std::vec![$($done,)* $($crate::resp!(@value $a),)*]
+    };
+    (@seq [$($done:expr,)*] [] $($a:tt $b:literal),+ $(,)?) => {
+        ::std::vec![$($done,)* $($crate::resp!(@value $a $b),)*]
+    };
+    // The next eight items are single tokens.
+    (@seq [$($done:expr,)*] [] $a:tt, $b:tt, $c:tt, $d:tt, $e:tt, $f:tt, $g:tt, $h:tt, $($rest:tt)*) => {
+        $crate::resp!(
+            @seq [
+                $($done,)*
+                $crate::resp!(@value $a), $crate::resp!(@value $b),
+                $crate::resp!(@value $c), $crate::resp!(@value $d),
+                $crate::resp!(@value $e), $crate::resp!(@value $f),
+                $crate::resp!(@value $g), $crate::resp!(@value $h),
+            ] [] $($rest)*
+        )
+    };
+    // The current item ends within the next four tokens.
+    (@seq [$($done:expr,)*] [$($item:tt)*] $a:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(@seq [$($done,)* $crate::resp!(@value $($item)* $a),] [] $($($rest)*)?)
+    };
+    (@seq [$($done:expr,)*] [$($item:tt)*] $a:tt $b:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(@seq [$($done,)* $crate::resp!(@value $($item)* $a $b),] [] $($($rest)*)?)
+    };
+    (@seq [$($done:expr,)*] [$($item:tt)*] $a:tt $b:tt $c:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(@seq [$($done,)* $crate::resp!(@value $($item)* $a $b $c),] [] $($($rest)*)?)
+    };
+    (@seq [$($done:expr,)*] [$($item:tt)*] $a:tt $b:tt $c:tt $d:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(
+            @seq [$($done,)* $crate::resp!(@value $($item)* $a $b $c $d),] [] $($($rest)*)?
+        )
+    };
+    // Otherwise the item is longer, so its next four tokens are collected.
+    (@seq [$($done:expr,)*] [$($item:tt)*] $a:tt $b:tt $c:tt $d:tt $($rest:tt)+) => {
+        $crate::resp!(@seq [$($done,)*] [$($item)* $a $b $c $d] $($rest)+)
+    };
+
+    // Entries of a map, split the same way as sequences: `@map` collects the key up to `=>`,
+    // then `@entry` the value up to the next comma.
+    (@map [$($done:expr,)*] [] $($k:tt => $v:tt),* $(,)?) => {
+        ::std::vec![$($done,)* $(($crate::resp!(@value $k), $crate::resp!(@value $v)),)*]
+    };
+    (@map [$($done:expr,)*] [] $($k:tt => $v:tt $w:literal),+ $(,)?) => {
+        ::std::vec![$($done,)* $(($crate::resp!(@value $k), $crate::resp!(@value $v $w)),)*]
+    };
+    (@map [$($done:expr,)*] [] $($k:tt $l:tt => $v:tt $w:literal),+ $(,)?) => {
+        ::std::vec![$($done,)* $(($crate::resp!(@value $k $l), $crate::resp!(@value $v $w)),)*]
+    };
+    (@map [$($done:expr,)*] [$($key:tt)*] $a:tt => $($rest:tt)*) => {
+        $crate::resp!(@entry [$($done,)*] [$($key)* $a] [] $($rest)*)
+    };
+    (@map [$($done:expr,)*] [$($key:tt)*] $a:tt $b:tt => $($rest:tt)*) => {
+        $crate::resp!(@entry [$($done,)*] [$($key)* $a $b] [] $($rest)*)
+    };
+    (@map [$($done:expr,)*] [$($key:tt)*] $a:tt $b:tt $c:tt => $($rest:tt)*) => {
+        $crate::resp!(@entry [$($done,)*] [$($key)* $a $b $c] [] $($rest)*)
+    };
+    (@map [$($done:expr,)*] [$($key:tt)*] $a:tt $b:tt $c:tt $d:tt => $($rest:tt)*) => {
+        $crate::resp!(@entry [$($done,)*] [$($key)* $a $b $c $d] [] $($rest)*)
+    };
+    (@map [$($done:expr,)*] [$($key:tt)*] $a:tt $b:tt $c:tt $d:tt $($rest:tt)+) => {
+        $crate::resp!(@map [$($done,)*] [$($key)* $a $b $c $d] $($rest)+)
+    };
+    (@entry [$($done:expr,)*] [$($key:tt)+] [$($value:tt)*] $a:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(@entry_done [$($done,)*] [$($key)+] [$($value)* $a] $($($rest)*)?)
+    };
+    (@entry [$($done:expr,)*] [$($key:tt)+] [$($value:tt)*] $a:tt $b:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(@entry_done [$($done,)*] [$($key)+] [$($value)* $a $b] $($($rest)*)?)
+    };
+    (@entry [$($done:expr,)*] [$($key:tt)+] [$($value:tt)*] $a:tt $b:tt $c:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(@entry_done [$($done,)*] [$($key)+] [$($value)* $a $b $c] $($($rest)*)?)
+    };
+    (@entry [$($done:expr,)*] [$($key:tt)+] [$($value:tt)*] $a:tt $b:tt $c:tt $d:tt $(, $($rest:tt)*)?) => {
+        $crate::resp!(@entry_done [$($done,)*] [$($key)+] [$($value)* $a $b $c $d] $($($rest)*)?)
+    };
+    (@entry [$($done:expr,)*] [$($key:tt)+] [$($value:tt)*] $a:tt $b:tt $c:tt $d:tt $($rest:tt)+) => {
+        $crate::resp!(@entry [$($done,)*] [$($key)+] [$($value)* $a $b $c $d] $($rest)+)
+    };
+    (@entry_done [$($done:expr,)*] [$($key:tt)+] [$($value:tt)+] $($rest:tt)*) => {
+        $crate::resp!(
+            @map [$($done,)* ($crate::resp!(@value $($key)+), $crate::resp!(@value $($value)+)),]
+            [] $($rest)*
+        )
+    };
+
+    ($($value:tt)+) => {{
+        let mut output = ::std::vec::Vec::new();
+        $crate::write_frame(&$crate::resp!(@value $($value)+), &mut output)
+            .expect("Value in resp! cannot be encoded");
+        output
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Value, to_bytes};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(resp!(simple "OK"), b"+OK\r\n");
+        assert_eq!(resp!(error "ERR oops"), b"-ERR oops\r\n");
+        assert_eq!(resp!(bulk b"\xff\x00"), b"$2\r\n\xff\x00\r\n");
+        assert_eq!(resp!(bulk_error "bad"), b"!3\r\nbad\r\n");
+        assert_eq!(resp!(verbatim "abc"), b"=7\r\ntxt:abc\r\n");
+        assert_eq!(resp!(verbatim "mkd" "# a"), b"=7\r\nmkd:# a\r\n");
+        let text = "b";
+        assert_eq!(
+            resp!([verbatim "mkd" text, verbatim text, 1]),
+            b"*3\r\n=5\r\nmkd:b\r\n=5\r\ntxt:b\r\n:1\r\n"
+        );
+        assert_eq!(
+            resp!(big "12345678901234567890123"),
+            b"(12345678901234567890123\r\n"
+        );
+        assert_eq!(resp!(null), b"_\r\n");
+        assert_eq!(resp!(-42), b":-42\r\n");
+        assert_eq!(resp!(1.5), b",1.5\r\n");
+        assert_eq!(resp!(false), b"#f\r\n");
+        assert_eq!(resp!("text"), b"$4\r\ntext\r\n");
+    }
+
+    #[test]
+    fn test_expressions() {
+        let name = "Alice";
+        let scores = vec![1, 2];
+        assert_eq!(resp!(simple name), b"+Alice\r\n");
+        assert_eq!(resp!(scores), to_bytes(&scores).unwrap());
+        assert_eq!(
+            resp!([1 + 1, simple format!("{name}!")]),
+            b"*2\r\n:2\r\n+Alice!\r\n"
+        );
+        assert_eq!(resp!(Value::Integer(1)), b":1\r\n");
+    }
+
+    #[test]
+    fn test_aggregates() {
+        assert_eq!(resp!([]), b"*0\r\n");
+        assert_eq!(resp!(array [1, 2,]), b"*2\r\n:1\r\n:2\r\n");
+        assert_eq!(resp!(set [simple "a"]), b"~1\r\n+a\r\n");
+        assert_eq!(
+            resp!(push [simple "message", bulk "hi"]),
+            b">2\r\n+message\r\n$2\r\nhi\r\n"
+        );
+        assert_eq!(resp!(map {}), b"%0\r\n");
+        assert_eq!(
+            resp!(map { 1 => [null], simple "nested" => map { true => set [] }, }),
+            b"%2\r\n:1\r\n*1\r\n_\r\n+nested\r\n%1\r\n#t\r\n~0\r\n"
+        );
+        assert_eq!(
+            resp!(attributes { simple "ttl" => 3600 }),
+            b"|1\r\n+ttl\r\n:3600\r\n"
+        );
+    }
+
+    #[test]
+    fn test_long_aggregates() {
+        let items: Vec<u8> = (0..120).collect();
+        assert_eq!(
+            resp!([
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
+                23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43,
+                44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64,
+                65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85,
+                86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104,
+                105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119
+            ]),
+            to_bytes(&items).unwrap()
+        );
+        let mut expected = b"*100\r\n".to_vec();
+        for i in 0..50 {
+            expected.extend(format!("+{i}\r\n:{i}\r\n").bytes());
+        }
+        assert_eq!(
+            resp!([
+                simple "0", 0, simple "1", 1, simple "2", 2, simple "3", 3, simple
+                "4", 4, simple "5", 5, simple "6", 6, simple "7", 7, simple "8", 8,
+                simple "9", 9, simple "10", 10, simple "11", 11, simple "12", 12,
+                simple "13", 13, simple "14", 14, simple "15", 15, simple "16", 16,
+                simple "17", 17, simple "18", 18, simple "19", 19, simple "20", 20,
+                simple "21", 21, simple "22", 22, simple "23", 23, simple "24", 24,
+                simple "25", 25, simple "26", 26, simple "27", 27, simple "28", 28,
+                simple "29", 29, simple "30", 30, simple "31", 31, simple "32", 32,
+                simple "33", 33, simple "34", 34, simple "35", 35, simple "36", 36,
+                simple "37", 37, simple "38", 38, simple "39", 39, simple "40", 40,
+                simple "41", 41, simple "42", 42, simple "43", 43, simple "44", 44,
+                simple "45", 45, simple "46", 46, simple "47", 47, simple "48", 48,
+                simple "49", 49,
+            ]),
+            expected
+        );
+        let entries: BTreeMap<_, _> = (0..110).map(|i| (format!("k{i}"), i)).collect();
+        let encoded = resp!(map {
+            "k0" => 0, "k1" => 1, "k2" => 2, "k3" => 3, "k4" => 4, "k5" => 5, "k6" => 6,
+            "k7" => 7, "k8" => 8, "k9" => 9, "k10" => 10, "k11" => 11, "k12" => 12,
+            "k13" => 13, "k14" => 14, "k15" => 15, "k16" => 16, "k17" => 17, "k18" =>
+            18, "k19" => 19, "k20" => 20, "k21" => 21, "k22" => 22, "k23" => 23, "k24"
+            => 24, "k25" => 25, "k26" => 26, "k27" => 27, "k28" => 28, "k29" => 29,
+            "k30" => 30, "k31" => 31, "k32" => 32, "k33" => 33, "k34" => 34, "k35" =>
+            35, "k36" => 36, "k37" => 37, "k38" => 38, "k39" => 39, "k40" => 40, "k41"
+            => 41, "k42" => 42, "k43" => 43, "k44" => 44, "k45" => 45, "k46" => 46,
+            "k47" => 47, "k48" => 48, "k49" => 49, "k50" => 50, "k51" => 51, "k52" =>
+            52, "k53" => 53, "k54" => 54, "k55" => 55, "k56" => 56, "k57" => 57, "k58"
+            => 58, "k59" => 59, "k60" => 60, "k61" => 61, "k62" => 62, "k63" => 63,
+            "k64" => 64, "k65" => 65, "k66" => 66, "k67" => 67, "k68" => 68, "k69" =>
+            69, "k70" => 70, "k71" => 71, "k72" => 72, "k73" => 73, "k74" => 74, "k75"
+            => 75, "k76" => 76, "k77" => 77, "k78" => 78, "k79" => 79, "k80" => 80,
+            "k81" => 81, "k82" => 82, "k83" => 83, "k84" => 84, "k85" => 85, "k86" =>
+            86, "k87" => 87, "k88" => 88, "k89" => 89, "k90" => 90, "k91" => 91, "k92"
+            => 92, "k93" => 93, "k94" => 94, "k95" => 95, "k96" => 96, "k97" => 97,
+            "k98" => 98, "k99" => 99, "k100" => 100, "k101" => 101, "k102" => 102,
+            "k103" => 103, "k104" => 104, "k105" => 105, "k106" => 106, "k107" => 107,
+            "k108" => 108, "k109" => 109,
+        });
+        assert_eq!(
+            crate::from_bytes::<BTreeMap<String, i32>>(&encoded).unwrap(),
+            entries
+        );
+    }
+
+    #[test]
+    fn test_long_items() {
+        let name = "Alice";
+        assert_eq!(
+            resp!([simple name.to_uppercase(), 1 + 2 + 3 + 4, map { 2 * 2 + 2 => -(1 + 1) }]),
+            b"*3\r\n+ALICE\r\n:10\r\n%1\r\n:6\r\n:-2\r\n"
+        );
+    }
+
+    #[test]
+    #[should_panic = "cannot be encoded"]
+    fn test_invalid() {
+        resp!(simple "a\r\nb");
+    }
+
+    #[test]
+    #[should_panic = "cannot be encoded"]
+    fn test_invalid_encoding() {
+        resp!(verbatim "markdown" "# a");
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{Deserializer, Error, RespDataKind, Result};
+use serde::de::value::{BorrowedBytesDeserializer, BorrowedStrDeserializer};
+use serde::de::{MapAccess, Visitor};
+
+/// Name of the newtype struct used to pass raw RESP data through serde.
+///
+/// When deserializing, [`crate::Deserializer`] answers it with the bytes of the next complete
+/// value (see [`RawAccess`]). When serializing, [`crate::Serializer`] writes the wrapped bytes as they are.
+pub(crate) const TOKEN: &str = "$rediserde::RawResp";
+
+/// A single, complete RESP value kept as its raw bytes.
+///
+/// Similar to `serde_json::value::RawValue`: when a field is typed `&RawResp` (or `Box<RawResp>`),
+/// deserializing captures the exact bytes of one value without decoding it, and serializing
+/// writes those bytes back as they are. Useful for forwarding parts of a reply untouched.
+///
+/// ```
+/// # use rediserde::{RawResp, from_str, to_string};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Deserialize, Serialize)]
+/// struct Reply<'a> {
+///     id: u32,
+///     #[serde(borrow)]
+///     payload: &'a RawResp,
+/// }
+///
+/// let raw = "%2\r\n+id\r\n:1\r\n+payload\r\n*2\r\n+a\r\n:2\r\n";
+/// let reply: Reply = from_str(raw).unwrap();
+/// assert_eq!(reply.payload.as_bytes(), b"*2\r\n+a\r\n:2\r\n");
+/// assert_eq!(to_string(&reply).unwrap(), "%2\r\n$2\r\nid\r\n:1\r\n$7\r\npayload\r\n*2\r\n+a\r\n:2\r\n");
+/// ```
+///
+/// `&RawResp` borrows from the input, so it only works with [`crate::from_bytes`] and
+/// [`crate::from_str`]. `Box<RawResp>` copies the bytes and has no such restriction.
+#[repr(transparent)]
+pub struct RawResp {
+    resp: [u8],
+}
+
+impl RawResp {
+    const fn from_borrowed(resp: &[u8]) -> &Self {
+        // SAFETY: `RawResp` is `repr(transparent)` over `[u8]`, so the layouts are identical.
+        unsafe { &*(std::ptr::from_ref::<[u8]>(resp) as *const Self) }
+    }
+
+    fn from_owned(resp: Box<[u8]>) -> Box<Self> {
+        // SAFETY: `RawResp` is `repr(transparent)` over `[u8]`, so the layouts are identical.
+        unsafe { Box::from_raw(Box::into_raw(resp) as *mut Self) }
+    }
+
+    /// Checks that the bytes hold exactly one complete RESP value and wraps them.
+    pub fn from_bytes(resp: &[u8]) -> Result<&Self> {
+        let mut deserializer = Deserializer::new(resp);
+        deserializer.skip_frame()?;
+        if !deserializer.is_empty() {
+            return Err(Error::DeserializeError(
+                "Expected a single RESP value, found trailing bytes".to_string(),
+            ));
+        }
+        Ok(Self::from_borrowed(resp))
+    }
+
+    /// Same as [`RawResp::from_bytes`], but takes ownership of the data.
+    pub fn from_vec(resp: Vec<u8>) -> Result<Box<Self>> {
+        Self::from_bytes(&resp)?;
+        Ok(Self::from_owned(resp.into_boxed_slice()))
+    }
+
+    /// The raw bytes of the value, including its prefix and trailing CRLF.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.resp
+    }
+
+    /// The kind of the value, taken from its prefix.
+    #[must_use]
+    pub fn kind(&self) -> RespDataKind {
+        // Every way of building a `RawResp` checks the value, so the fallback is never used
+        self.resp
+            .first()
+            .and_then(|&prefix| RespDataKind::try_from(prefix).ok())
+            .unwrap_or(RespDataKind::Null)
+    }
+}
+
+impl std::fmt::Debug for RawResp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RawResp")
+            .field(&String::from_utf8_lossy(&self.resp))
+            .finish()
+    }
+}
+
+impl PartialEq for RawResp {
+    fn eq(&self, other: &Self) -> bool {
+        self.resp == other.resp
+    }
+}
+
+impl Eq for RawResp {}
+
+impl ToOwned for RawResp {
+    type Owned = Box<Self>;
+
+    fn to_owned(&self) -> Box<Self> {
+        Self::from_owned(self.resp.into())
+    }
+}
+
+impl Clone for Box<RawResp> {
+    fn clone(&self) -> Self {
+        (**self).to_owned()
+    }
+}
+
+impl serde::Serialize for RawResp {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct(TOKEN, &RawBytes(&self.resp))
+    }
+}
+
+/// Serializes the wrapped data with `serialize_bytes`.
+struct RawBytes<'a>(&'a [u8]);
+
+impl serde::Serialize for RawBytes<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl<'de: 'a, 'a> serde::Deserialize<'de> for &'a RawResp {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct RawVisitor;
+
+        impl<'de> Visitor<'de> for RawVisitor {
+            type Value = &'de RawResp;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a borrowed RESP value")
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                RawResp::from_bytes(v).map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let frame = raw_frame(map)?;
+                RawResp::from_bytes(frame).map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TOKEN, RawVisitor)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Box<RawResp> {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct BoxedRawVisitor;
+
+        impl<'de> Visitor<'de> for BoxedRawVisitor {
+            type Value = Box<RawResp>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a RESP value")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                RawResp::from_vec(v.to_vec()).map_err(E::custom)
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                RawResp::from_vec(v).map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let frame = raw_frame(map)?;
+                RawResp::from_vec(frame.to_vec()).map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TOKEN, BoxedRawVisitor)
+    }
+}
+
+/// Hands the bytes of the next value from [`crate::Deserializer`] to the visitors of
+/// [`RawResp`], as a map with a single [`TOKEN`] key.
+///
+/// Any deserializer can give a map like this, so the visitors still check the bytes.
+pub(crate) struct RawAccess<'de>(pub(crate) Option<&'de [u8]>);
+
+impl<'de> MapAccess<'de> for RawAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: serde::de::DeserializeSeed<'de>,
+    {
+        if self.0.is_none() {
+            return Ok(None);
+        }
+        seed.deserialize(BorrowedStrDeserializer::new(TOKEN))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let frame = self.0.take().ok_or(Error::UnexpectedEnd)?;
+        seed.deserialize(BorrowedBytesDeserializer::new(frame))
+    }
+}
+
+/// The bytes in a map like the one given by [`RawAccess`], any other map is rejected.
+fn raw_frame<'de, A>(mut map: A) -> std::result::Result<&'de [u8], A::Error>
+where
+    A: MapAccess<'de>,
+{
+    match map.next_key::<&str>()? {
+        Some(TOKEN) => map.next_value(),
+        _ => Err(serde::de::Error::custom(
+            "A RawResp can only be read from a map by rediserde",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_bytes, from_str, to_bytes, to_string};
+
+    #[test]
+    fn test_from_bytes() {
+        assert!(RawResp::from_bytes(b"*2\r\n:1\r\n%1\r\n+a\r\n#t\r\n").is_ok());
+        assert!(RawResp::from_bytes(b"*2\r\n:1\r\n").is_err(), "incomplete");
+        assert!(RawResp::from_bytes(b":1\r\n:2\r\n").is_err(), "trailing");
+        assert!(RawResp::from_bytes(b"").is_err(), "empty");
+        assert!(
+            RawResp::from_bytes(b"%?\r\n+a\r\n.\r\n").is_err(),
+            "key without a value"
+        );
+        let raw = RawResp::from_bytes(b"~1\r\n$1\r\na\r\n").unwrap();
+        assert_eq!(raw.kind(), RespDataKind::Set);
+    }
+
+    #[test]
+    fn test_pipeline_elements() {
+        let raw = b"*3\r\n+OK\r\n%1\r\n$1\r\na\r\n*-1\r\n$-1\r\n";
+        let elements: Vec<&RawResp> = from_bytes(raw).unwrap();
+        let elements: Vec<&[u8]> = elements.iter().map(|e| e.as_bytes()).collect();
+        assert_eq!(
+            elements,
+            vec![&b"+OK\r\n"[..], b"%1\r\n$1\r\na\r\n*-1\r\n", b"$-1\r\n"]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let raw = "*2\r\n+OK\r\n|1\r\n+key\r\n,1.5\r\n";
+        let borrowed: &RawResp = from_str(raw).unwrap();
+        assert_eq!(to_string(&borrowed).unwrap(), raw);
+        let boxed: Vec<Box<RawResp>> = from_str(raw).unwrap();
+        assert_eq!(boxed.len(), 2);
+        assert_eq!(to_string(&boxed).unwrap(), raw);
+        assert_eq!(boxed[1].clone(), boxed[1]);
+        assert_eq!(to_bytes(&*boxed[1]).unwrap(), b"|1\r\n+key\r\n,1.5\r\n");
+    }
+
+    #[test]
+    fn test_other_deserializers() {
+        use serde::Deserialize;
+        use serde::de::value::{BorrowedBytesDeserializer, Error as ValueError, MapDeserializer};
+
+        let valid = BorrowedBytesDeserializer::<ValueError>::new(b"+OK\r\n");
+        assert_eq!(
+            <&RawResp>::deserialize(valid).unwrap().as_bytes(),
+            b"+OK\r\n"
+        );
+        let invalid = BorrowedBytesDeserializer::<ValueError>::new(b"+OK");
+        assert!(<&RawResp>::deserialize(invalid).is_err(), "Still checked");
+
+        let map = |value| {
+            let entry = (
+                BorrowedStrDeserializer::new(TOKEN),
+                BorrowedStrDeserializer::new(value),
+            );
+            MapDeserializer::<_, ValueError>::new([entry].into_iter())
+        };
+        for invalid in ["", "garbage", "+OK\r\n:1\r\n"] {
+            let err = <&RawResp>::deserialize(map(invalid)).unwrap_err();
+            assert!(!err.to_string().contains("invalid type"), "{err}");
+            assert!(
+                Box::<RawResp>::deserialize(map(invalid)).is_err(),
+                "{invalid:?}"
+            );
+        }
+        let raw = <&RawResp>::deserialize(map(":1\r\n")).unwrap();
+        assert_eq!(raw.kind(), RespDataKind::Integer);
+    }
+}
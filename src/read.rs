@@ -0,0 +1,657 @@
+//! Deserializing RESP data directly from a [`std::io::Read`].
+
+use crate::de::{Header, Pending};
+use crate::{CRLF, CRLF_STR, Error, RespDataKind, Result, STREAMED, STREAMED_CHUNK, STREAMED_END};
+use serde::de::DeserializeOwned;
+use std::borrow::Cow;
+use std::io::Read;
+
+/// Reads exactly one complete RESP value from `reader` and deserializes it.
+///
+/// Nothing after the value is read, so the same reader can be used for the next value.
+/// Headers are read one byte at a time, so wrapping unbuffered readers (like a `TcpStream`)
+/// in a [`std::io::BufReader`] is recommended, passing `&mut` to keep using it. A line longer
+/// than 64 KiB (like a never ending simple string) fails with [`Error::LineTooLong`].
+///
+/// ```
+/// # use rediserde::from_reader;
+/// # use std::io::{BufReader, Read};
+/// let mut reader = BufReader::new(&b"*2\r\n:1\r\n:2\r\n+OK\r\n"[..]);
+/// let numbers: Vec<u32> = from_reader(&mut reader).unwrap();
+/// assert_eq!(numbers, vec![1, 2]);
+/// let status: String = from_reader(&mut reader).unwrap();
+/// assert_eq!(status, "OK");
+/// ```
+pub fn from_reader<R, T>(mut reader: R) -> Result<T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut frame = Vec::new();
+    read_frame(&mut reader, &mut frame)?;
+    crate::from_bytes(&frame)
+}
+
+/// Reads the header of an array (or set, or push) from `reader`, and returns an iterator
+/// that reads and deserializes its elements one at a time, like [`crate::Deserializer::iter_array`].
+///
+/// Only one element is in memory at a time, so huge replies (like `SMEMBERS` of a big set)
+/// can be processed as they arrive. As with [`from_reader`], nothing after the array is read.
+///
+/// ```
+/// # use rediserde::iter_array_from_reader;
+/// # use std::io::BufReader;
+/// let reader = BufReader::new(&b"*3\r\n:1\r\n:2\r\n:3\r\n"[..]);
+/// let mut sum = 0;
+/// for number in iter_array_from_reader::<_, u64>(reader).unwrap() {
+///     sum += number.unwrap();
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+pub fn iter_array_from_reader<R, T>(mut reader: R) -> Result<ReaderArrayIter<R, T>>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut line = Vec::new();
+    read_line(&mut reader, &mut line)?;
+    let kind = RespDataKind::try_from(line[0]).map_err(|()| Error::UnrecognizedStart)?;
+    let remaining = match kind {
+        RespDataKind::Array | RespDataKind::Set | RespDataKind::Push => {
+            if line[1..] == [STREAMED, b'\r', b'\n'] {
+                None
+            } else {
+                Some(header_length(&line)?.unwrap_or_default())
+            }
+        }
+        RespDataKind::Null if line == b"_\r\n" => Some(0),
+        _ => {
+            return Err(Error::DeserializeError(
+                "Expected an array, set, or push".to_string(),
+            ));
+        }
+    };
+    Ok(ReaderArrayIter {
+        reader,
+        remaining,
+        frame: line,
+        failed: false,
+        output: std::marker::PhantomData,
+    })
+}
+
+/// A lazy iterator over the elements of an array from a reader, see [`iter_array_from_reader`].
+pub struct ReaderArrayIter<R, T> {
+    reader: R,
+    /// The number of elements left, `None` for streamed arrays which end with a marker.
+    remaining: Option<usize>,
+    /// The bytes of the current element, reused for all of them.
+    frame: Vec<u8>,
+    failed: bool,
+    output: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<R, T> ReaderArrayIter<R, T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    /// Returns the underlying reader. Unless the iterator is exhausted, it's in the
+    /// middle of the array.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn next_element(&mut self) -> Result<Option<T>> {
+        match &mut self.remaining {
+            Some(0) => return Ok(None),
+            Some(remaining) => *remaining -= 1,
+            None => {}
+        }
+        self.frame.clear();
+        read_line(&mut self.reader, &mut self.frame)?;
+        if self.remaining.is_none() && self.frame == STREAMED_END {
+            self.remaining = Some(0);
+            return Ok(None);
+        }
+        read_frame_rest(&mut self.reader, &mut self.frame, 0)?;
+        crate::from_bytes(&self.frame).map(Some)
+    }
+}
+
+impl<R, T> Iterator for ReaderArrayIter<R, T>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_element();
+        self.failed = next.is_err();
+        next.transpose()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            _ if self.failed => (0, Some(0)),
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (0, None),
+        }
+    }
+}
+
+/// Reads the header of a bulk string (or bulk error, or verbatim string) from `reader`, and
+/// returns a reader over exactly its payload, so large values never have to be in memory at once.
+///
+/// The CRLF after the payload is consumed with its last byte, so once the payload is read to
+/// the end, `reader` is at the start of the next value. Nulls are read as empty payloads.
+/// Streamed strings have no length up front and are not supported.
+///
+/// ```
+/// # use rediserde::{from_reader, read_bulk};
+/// # use std::io::BufReader;
+/// let mut reader = BufReader::new(&b"$11\r\nHello World\r\n:1\r\n"[..]);
+/// let mut bulk = read_bulk(&mut reader).unwrap();
+/// assert_eq!(bulk.len(), 11);
+/// let mut sink = Vec::new(); // e.g. a `File`
+/// std::io::copy(&mut bulk, &mut sink).unwrap();
+/// assert_eq!(sink, b"Hello World");
+/// assert_eq!(from_reader::<_, u8>(&mut reader).unwrap(), 1);
+/// ```
+pub fn read_bulk<R>(mut reader: R) -> Result<BulkReader<R>>
+where
+    R: Read,
+{
+    let mut line = Vec::new();
+    read_line(&mut reader, &mut line)?;
+    let kind = RespDataKind::try_from(line[0]).map_err(|()| Error::UnrecognizedStart)?;
+    let len = match kind {
+        RespDataKind::BulkString | RespDataKind::BulkError | RespDataKind::VerbatimString => {
+            header_length(&line)?
+        }
+        RespDataKind::Null if line == b"_\r\n" => None,
+        _ => {
+            return Err(Error::UnexpectedByte {
+                expected: "A bulk string".to_string(),
+                found: char::from(line[0]),
+            });
+        }
+    };
+    let mut bulk = BulkReader {
+        reader,
+        kind: if len.is_some() {
+            kind
+        } else {
+            RespDataKind::Null
+        },
+        len: len.unwrap_or_default(),
+        remaining: len.unwrap_or_default(),
+    };
+    if len == Some(0) {
+        bulk.read_crlf()?;
+    }
+    Ok(bulk)
+}
+
+/// A reader over the payload of a bulk string, see [`read_bulk`].
+#[derive(Debug)]
+pub struct BulkReader<R> {
+    reader: R,
+    kind: RespDataKind,
+    len: usize,
+    remaining: usize,
+}
+
+impl<R> BulkReader<R>
+where
+    R: Read,
+{
+    /// The kind of the value, [`RespDataKind::Null`] for nulls.
+    #[must_use]
+    pub const fn kind(&self) -> RespDataKind {
+        self.kind
+    }
+
+    /// The length of the whole payload, including the parts already read.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the underlying reader. Unless the payload was read to the end, it's in
+    /// the middle of the value.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_crlf(&mut self) -> std::io::Result<()> {
+        let mut crlf = [0; 2];
+        self.reader.read_exact(&mut crlf)?;
+        if crlf == CRLF {
+            Ok(())
+        } else {
+            let err = Error::UnexpectedByte {
+                expected: CRLF_STR.to_string(),
+                found: char::from(if crlf[0] == CRLF[0] { crlf[1] } else { crlf[0] }),
+            };
+            Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        }
+    }
+}
+
+impl<R> Read for BulkReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        let max = buf.len().min(self.remaining);
+        let read = self.reader.read(&mut buf[..max])?;
+        if read == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        self.remaining -= read;
+        if self.remaining == 0 {
+            self.read_crlf()?;
+        }
+        Ok(read)
+    }
+}
+
+/// Defines `read_frame` (and the functions it uses) for one kind of reader, so the blocking
+/// and the async readers read frames the same way. Async readers pass `async` and `[.await]`,
+/// blocking ones leave them out. The traits with the reading methods must be in scope.
+macro_rules! impl_read_frame {
+    ($($async:ident)?, [$($await:tt)*], $($reader:tt)+) => {
+        /// Reads the bytes of one complete RESP value to the end of `frame`.
+        ///
+        /// Only the structure is checked here (prefixes and lengths), the rest is up to the
+        /// deserializer that gets the bytes.
+        $($async)? fn read_frame<R>(reader: &mut R, frame: &mut Vec<u8>) -> $crate::Result<()>
+        where
+            R: $($reader)+,
+        {
+            let start = frame.len();
+            read_line(reader, frame)$($await)*?;
+            read_frame_rest(reader, frame, start)$($await)*
+        }
+
+        /// Like `read_frame`, for a frame whose first line was already read to `frame[start..]`.
+        $($async)? fn read_frame_rest<R>(
+            reader: &mut R,
+            frame: &mut Vec<u8>,
+            start: usize,
+        ) -> $crate::Result<()>
+        where
+            R: $($reader)+,
+        {
+            use $crate::read::{FrameState, Next};
+            let mut state = FrameState::new();
+            let mut next = state.line(&frame[start..])?;
+            loop {
+                let start = frame.len();
+                match next {
+                    Next::Line => {
+                        read_line(reader, frame)$($await)*?;
+                        next = state.line(&frame[start..])?;
+                    }
+                    Next::Payload(length) => {
+                        read_exact(reader, length, frame)$($await)*?;
+                        next = state.payload();
+                    }
+                    Next::Done => return Ok(()),
+                }
+            }
+        }
+
+        /// Reads up to and including the next LF, one byte at a time so nothing more is read.
+        $($async)? fn read_line<R>(reader: &mut R, frame: &mut Vec<u8>) -> $crate::Result<()>
+        where
+            R: $($reader)+,
+        {
+            let start = frame.len();
+            let mut byte = [0];
+            loop {
+                if frame.len() - start == $crate::read::MAX_LINE_LENGTH {
+                    return Err($crate::Error::LineTooLong);
+                }
+                reader.read_exact(&mut byte)$($await)*?;
+                frame.push(byte[0]);
+                if byte[0] == b'\n' {
+                    return Ok(());
+                }
+            }
+        }
+
+        /// Reads exactly `length` bytes, without allocating all of them up front.
+        $($async)? fn read_exact<R>(
+            reader: &mut R,
+            length: usize,
+            frame: &mut Vec<u8>,
+        ) -> $crate::Result<()>
+        where
+            R: $($reader)+,
+        {
+            let length = u64::try_from(length).map_err(|_| $crate::Error::ExpectedLength)?;
+            let read = reader.take(length).read_to_end(frame)$($await)*?;
+            if read as u64 == length {
+                Ok(())
+            } else {
+                Err($crate::Error::UnexpectedEnd)
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "tokio", feature = "futures"))]
+pub(crate) use impl_read_frame;
+
+impl_read_frame!(, [], Read);
+
+/// What to read next, see [`FrameState`].
+pub(crate) enum Next {
+    /// A line up to and including the next LF.
+    Line,
+    /// Exactly this many bytes, the data of a bulk value and its CRLF.
+    Payload(usize),
+    /// The frame is complete.
+    Done,
+}
+
+/// Follows the structure of a frame as it's read, telling the reader what to read next.
+///
+/// Keeps the reading itself separate, so it's the same for all kinds of readers.
+pub(crate) struct FrameState {
+    pending: Pending,
+    /// Whether the chunks of a streamed string are being read.
+    chunks: bool,
+}
+
+impl FrameState {
+    /// Starts a frame, which begins with a line.
+    pub(crate) const fn new() -> Self {
+        Self {
+            pending: Pending::new(),
+            chunks: false,
+        }
+    }
+
+    /// Handles a complete line, including its CRLF.
+    pub(crate) fn line(&mut self, line: &[u8]) -> Result<Next> {
+        if self.chunks {
+            if line[0] != STREAMED_CHUNK {
+                return Err(Error::UnexpectedByte {
+                    expected: char::from(STREAMED_CHUNK).to_string(),
+                    found: char::from(line[0]),
+                });
+            }
+            return match header_length(line)? {
+                Some(0) => {
+                    self.chunks = false;
+                    Ok(self.next())
+                }
+                Some(length) => Ok(Next::Payload(payload_length(length)?)),
+                None => Err(Error::ExpectedLength),
+            };
+        }
+        if line == STREAMED_END {
+            self.pending.push(&Header::End)?;
+            return Ok(self.next());
+        }
+        let kind = RespDataKind::try_from(line[0]).map_err(|()| Error::UnrecognizedStart)?;
+        let streamed = line[1..] == [STREAMED, b'\r', b'\n'];
+        // Only the structure matters here, so scalars are counted without their data
+        let mut header = Header::Scalar(kind, Cow::Borrowed(b""));
+        let mut next = None;
+        match kind {
+            RespDataKind::BulkString | RespDataKind::BulkError | RespDataKind::VerbatimString => {
+                if streamed {
+                    self.chunks = true;
+                } else if let Some(length) = header_length(line)? {
+                    next = Some(Next::Payload(payload_length(length)?));
+                }
+            }
+            RespDataKind::Array
+            | RespDataKind::Set
+            | RespDataKind::Push
+            | RespDataKind::Map
+            | RespDataKind::Attributes => {
+                if streamed {
+                    header = Header::Aggregate(kind, None);
+                } else if let Some(length) = header_length(line)? {
+                    header = Header::Aggregate(kind, Some(length));
+                }
+            }
+            _ => {}
+        }
+        self.pending.push(&header)?;
+        Ok(next.unwrap_or_else(|| self.next()))
+    }
+
+    /// Handles a complete payload.
+    pub(crate) fn payload(&self) -> Next {
+        self.next()
+    }
+
+    fn next(&self) -> Next {
+        if self.chunks || !self.pending.is_done() {
+            Next::Line
+        } else {
+            Next::Done
+        }
+    }
+}
+
+/// The longest line a reader accepts, like the limit Redis has for inline commands.
+///
+/// Without it, a peer that never sends an LF would make the line grow without bounds.
+pub(crate) const MAX_LINE_LENGTH: usize = 64 * 1024;
+
+/// The length of a payload of `length` bytes with its CRLF.
+fn payload_length(length: usize) -> Result<usize> {
+    length.checked_add(CRLF.len()).ok_or(Error::ExpectedLength)
+}
+
+/// The length in a `<prefix><length>\r\n` header line, `None` for RESP2 nulls (`-1`).
+fn header_length(line: &[u8]) -> Result<Option<usize>> {
+    let length = line[1..].strip_suffix(CRLF).ok_or(Error::ExpectedLength)?;
+    if length == b"-1" {
+        return Ok(None);
+    }
+    if length.is_empty() || !length.iter().all(u8::is_ascii_digit) {
+        return Err(Error::ExpectedLength);
+    }
+    let length = str::from_utf8(length)?;
+    length.parse().map(Some).map_err(|_| Error::ExpectedLength)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_leaves_next_frame() {
+        let input = b"%2\r\n+a\r\n$4\r\n\r\n\r\n\r\n+b\r\n*-1\r\n$-1\r\n:5\r\n";
+        let mut reader = Cursor::new(&input[..]);
+        let value: Value = from_reader(&mut reader).unwrap();
+        assert_eq!(value["a"], Value::BulkString(b"\r\n\r\n".to_vec()));
+        assert_eq!(value["b"], Value::Null);
+        assert_eq!(reader.position(), 27);
+        let value: Value = from_reader(&mut reader).unwrap();
+        assert_eq!(value, Value::Null);
+        let value: i64 = from_reader(&mut reader).unwrap();
+        assert_eq!(value, 5);
+        assert!(matches!(
+            from_reader::<_, Value>(&mut reader),
+            Err(Error::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn test_iter_array() {
+        let input = b"*2\r\n*?\r\n:1\r\n.\r\n*0\r\n~?\r\n*1\r\n:2\r\n.\r\n*-1\r\n:3\r\n";
+        let mut reader = Cursor::new(&input[..]);
+        let elements = iter_array_from_reader::<_, Vec<u8>>(&mut reader).unwrap();
+        assert_eq!(elements.size_hint(), (2, Some(2)));
+        let elements = elements.collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(elements, [vec![1], vec![]]);
+        let elements = iter_array_from_reader::<_, Vec<u8>>(&mut reader).unwrap();
+        assert_eq!(elements.collect::<Result<Vec<_>>>().unwrap(), [vec![2]]);
+        assert_eq!(
+            iter_array_from_reader::<_, u8>(&mut reader)
+                .unwrap()
+                .count(),
+            0
+        );
+        assert_eq!(from_reader::<_, u8>(&mut reader).unwrap(), 3);
+
+        let input = b"*3\r\n:1\r\n:300\r\n:3\r\n";
+        let mut elements = iter_array_from_reader::<_, u8>(&input[..]).unwrap();
+        assert_eq!(elements.next().unwrap().unwrap(), 1);
+        assert!(elements.next().unwrap().is_err());
+        assert!(elements.next().is_none(), "Stops after an error");
+        let mut elements = iter_array_from_reader::<_, u8>(&b"*2\r\n:1\r\n"[..]).unwrap();
+        assert_eq!(elements.next().unwrap().unwrap(), 1);
+        assert!(matches!(elements.next(), Some(Err(Error::UnexpectedEnd))));
+        assert!(iter_array_from_reader::<_, u8>(&b":1\r\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_read_bulk() {
+        let input = b"$5\r\nHello\r\n!3\r\nERR\r\n$0\r\n\r\n$-1\r\n_\r\n:1\r\n";
+        let mut reader = Cursor::new(&input[..]);
+        let mut bulk = read_bulk(&mut reader).unwrap();
+        assert_eq!((bulk.kind(), bulk.len()), (RespDataKind::BulkString, 5));
+        let mut data = [0; 2];
+        bulk.read_exact(&mut data).unwrap();
+        assert_eq!(&data, b"He");
+        let mut data = Vec::new();
+        bulk.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"llo");
+        assert_eq!(reader.position(), 11, "The CRLF is consumed");
+
+        let mut bulk = read_bulk(&mut reader).unwrap();
+        assert_eq!(bulk.kind(), RespDataKind::BulkError);
+        let mut data = String::new();
+        bulk.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "ERR");
+        for kind in [
+            RespDataKind::BulkString,
+            RespDataKind::Null,
+            RespDataKind::Null,
+        ] {
+            let bulk = read_bulk(&mut reader).unwrap();
+            assert_eq!(bulk.kind(), kind);
+            assert!(bulk.is_empty());
+        }
+        assert_eq!(from_reader::<_, u8>(&mut reader).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_read_bulk_errors() {
+        let read_all = |input: &[u8]| -> Result<Vec<u8>> {
+            let mut data = Vec::new();
+            read_bulk(input)?.read_to_end(&mut data)?;
+            Ok(data)
+        };
+        assert!(matches!(read_all(b"$5\r\nHel"), Err(Error::UnexpectedEnd)));
+        assert!(matches!(
+            read_all(b"$5\r\nHello"),
+            Err(Error::UnexpectedEnd)
+        ));
+        assert!(read_all(b"$5\r\nHello!!").is_err());
+        assert!(read_all(b"$?\r\n;0\r\n").is_err(), "Streamed");
+        assert!(read_all(b"+Hello\r\n").is_err());
+        assert!(read_all(b"*1\r\n$1\r\na\r\n").is_err());
+    }
+
+    #[test]
+    fn test_streamed() {
+        let input =
+            b"*?\r\n$?\r\n;2\r\nab\r\n;3\r\n\r\n.\r\n;0\r\n%?\r\n.\r\n*1\r\n:1\r\n.\r\n:2\r\n";
+        let mut reader = Cursor::new(&input[..]);
+        let value: Value = from_reader(&mut reader).unwrap();
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::BulkString(b"ab\r\n.".to_vec()),
+                Value::Map(vec![]),
+                Value::Array(vec![Value::Integer(1)]),
+            ])
+        );
+        assert_eq!(from_reader::<_, u8>(&mut reader).unwrap(), 2);
+        assert!(matches!(
+            from_reader::<_, Value>(&b"*1\r\n.\r\n"[..]),
+            Err(Error::UnrecognizedStart)
+        ));
+        assert!(matches!(
+            from_reader::<_, Value>(&b"$?\r\n;2\r\nab"[..]),
+            Err(Error::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn test_truncated() {
+        for input in [&b"*2\r\n:1\r\n"[..], b"$5\r\nab", b":1\r"] {
+            assert!(
+                matches!(from_reader::<_, Value>(input), Err(Error::UnexpectedEnd)),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(
+            from_reader::<_, Value>(&b"?1\r\n"[..]),
+            Err(Error::UnrecognizedStart)
+        ));
+        assert!(matches!(
+            from_reader::<_, Value>(&b"$x\r\n"[..]),
+            Err(Error::ExpectedLength)
+        ));
+        assert!(from_reader::<_, u8>(&b":300\r\n"[..]).is_err());
+        for input in [
+            &b"$18446744073709551615\r\nab"[..],
+            b"$?\r\n;18446744073709551615\r\nab",
+        ] {
+            assert!(
+                matches!(from_reader::<_, Value>(input), Err(Error::ExpectedLength)),
+                "{input:?}"
+            );
+        }
+        let mut long = b"+".to_vec();
+        long.resize(MAX_LINE_LENGTH + 1, b'a');
+        assert!(matches!(
+            from_reader::<_, Value>(&long[..]),
+            Err(Error::LineTooLong)
+        ));
+    }
+
+    #[test]
+    fn test_io_error() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("connection reset"))
+            }
+        }
+
+        let err = from_reader::<_, Value>(Failing).unwrap_err();
+        assert!(matches!(&err, Error::Io(io) if io.to_string() == "connection reset"));
+    }
+}
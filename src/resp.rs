@@ -156,6 +156,7 @@ pub enum RespDataKind {
     /// Represents a RESP [Float](https://redis.io/docs/latest/develop/reference/protocol-spec/#floats)
     ///
     /// Prefix: `,` | for example, `,3.1\r\n`, `,+3.1\r\n` for positive and `,-3.1\r\n` for negative
+    /// (also `,inf\r\n`, `,-inf\r\n` and `,nan\r\n`)
     /// Serialized and deserialized as a both [`f32`] and [`f64`]
     ///
     /// ```
@@ -169,13 +170,14 @@ pub enum RespDataKind {
     /// Prefix: `(` | for example, `(12345678901234567890\r\n`
     ///
     /// The only numeric Rust types that can be serialized to this type are
-    /// [`u64`] and [`usize`], since the max range of normal RESP integers is 64 bits
-    /// (including negative values). These types automatically convert to this RESP type,
-    /// even if they are "smaller" than 64 bits to make the logic consistent.
+    /// [`u64`], [`usize`], [`u128`] and [`i128`], since the max range of normal RESP integers
+    /// is 64 bits (including negative values). These types automatically convert to this RESP
+    /// type, even if their value is small, to make the logic consistent.
     /// This behavior may change in the future.
     ///
-    /// Currently, [`u128`] and [`i128`] are not supported
-    /// by serde, so they cannot be used with this crate (at the moment).
+    /// [`u128`] and [`i128`] can also be deserialized from RESP integers, and from simple or
+    /// bulk strings of digits, failing if the value is out of their range.
+    /// Values of any size can be kept with [`crate::BigNumber`].
     ///
     /// ```
     /// # use rediserde::{from_str, to_string};
@@ -185,6 +187,10 @@ pub enum RespDataKind {
     /// let num64_deserialized: u64 = from_str(&num64_str).unwrap();
     /// // but within range, this can also be coerced to a `u8` for example
     /// let num8: u8 = from_str(&num64_str).unwrap();
+    ///
+    /// let num128: i128 = from_str("(-170141183460469231731687303715884105728\r\n").unwrap();
+    /// assert_eq!(num128, i128::MIN);
+    /// assert_eq!(to_string(&num128).unwrap(), "(-170141183460469231731687303715884105728\r\n");
     /// ```
     BigNumber,
     /// Represents a RESP [Bulk Error](https://redis.io/docs/latest/develop/reference/protocol-spec/#bulk-errors)
@@ -250,9 +256,9 @@ pub enum RespDataKind {
     ///
     /// ```
     /// # use rediserde::{from_str, to_string};
-    /// # use std::collections::{HashMap, BTreeMap};
+    /// # use std::collections::BTreeMap;
     ///
-    /// let mut map = HashMap::new();
+    /// let mut map = BTreeMap::new();
     /// map.insert("first_name".to_string(), "Alice".to_string());
     /// map.insert("last_name".to_string(), "Smith".to_string());
     ///
@@ -357,6 +363,49 @@ impl RespDataKind {
     fn from_prefix_bytes(b: u8) -> Option<Self> {
         Self::from_prefix_char(char::from(b))
     }
+
+    /// The number of values following the header of an aggregate of this kind
+    /// with the given length, which counts pairs for maps and attributes.
+    pub(crate) fn children(self, length: usize) -> crate::Result<usize> {
+        match self {
+            Self::Map | Self::Attributes => {
+                length.checked_mul(2).ok_or(crate::Error::ExpectedLength)
+            }
+            _ => Ok(length),
+        }
+    }
+
+    /// The name of a newtype struct that marks its content as being of this kind.
+    ///
+    /// [`crate::Serializer`] uses it to write kinds that can't be expressed through the serde
+    /// data model alone (like simple strings or sets), other serializers just see a newtype struct.
+    pub(crate) const fn to_token(self) -> &'static str {
+        match self {
+            Self::SimpleString => "$rediserde::kind::+",
+            Self::SimpleError => "$rediserde::kind::-",
+            Self::Integer => "$rediserde::kind:::",
+            Self::BulkString => "$rediserde::kind::$",
+            Self::Array => "$rediserde::kind::*",
+            Self::Null => "$rediserde::kind::_",
+            Self::Boolean => "$rediserde::kind::#",
+            Self::Float => "$rediserde::kind::,",
+            Self::BigNumber => "$rediserde::kind::(",
+            Self::BulkError => "$rediserde::kind::!",
+            Self::VerbatimString => "$rediserde::kind::=",
+            Self::Map => "$rediserde::kind::%",
+            Self::Attributes => "$rediserde::kind::|",
+            Self::Set => "$rediserde::kind::~",
+            Self::Push => "$rediserde::kind::>",
+        }
+    }
+
+    pub(crate) fn from_token(name: &str) -> Option<Self> {
+        let prefix = name.strip_prefix("$rediserde::kind::")?.as_bytes();
+        match prefix {
+            [b] => Self::from_prefix_bytes(*b),
+            _ => None,
+        }
+    }
 }
 
 impl From<RespDataKind> for u8 {
@@ -1,16 +1,70 @@
 #![allow(clippy::missing_errors_doc)]
 
-use crate::{CRLF, Error, Result, resp::RespDataKind};
-
+use crate::frame::format_float;
+use crate::{CRLF, Error, Result, STREAMED, STREAMED_END, resp::RespDataKind};
+use std::io::{Read, Write};
+
+/// Serializes RESP data into any [`Write`], by default into a `Vec<u8>`.
+///
+/// The serializer may be reused for many values, and the output is never flushed.
+///
+/// ```
+/// # use rediserde::Serializer;
+/// # use serde::Serialize;
+/// let mut serializer = Serializer::new();
+/// ["PING"].serialize(&mut serializer).unwrap();
+/// assert_eq!(serializer.get_ref(), b"*1\r\n$4\r\nPING\r\n");
+/// serializer.clear();
+/// 1.serialize(&mut serializer).unwrap();
+/// assert_eq!(serializer.into_inner(), b":1\r\n");
+/// ```
 #[derive(Debug, Default)]
-pub struct Serializer {
-    output: Vec<u8>,
+pub struct Serializer<W = Vec<u8>> {
+    output: W,
+    /// The kind requested for the next value by a marker newtype struct, see `RespDataKind::to_token`.
+    kind: Option<RespDataKind>,
+    /// Set by [`crate::RawResp`], the next bytes are written as-is.
+    raw: bool,
+    /// How each open aggregate ends.
+    open: Vec<Aggregate>,
+    /// See [`Serializer::count_unknown_lengths`].
+    count_lengths: bool,
+    /// The number of open aggregates with `Aggregate::Counted`.
+    counted: usize,
+    /// Holds the output while counted aggregates are open, since their headers are only
+    /// known at the end.
+    buffer: Vec<u8>,
+}
+
+/// How an open aggregate ends.
+#[derive(Debug)]
+enum Aggregate {
+    /// The length was written in the header, nothing to do.
+    Known,
+    /// A streamed aggregate, ended with a marker.
+    Streamed,
+    /// The header is written at `header` in the buffer once all elements are counted.
+    Counted {
+        kind: RespDataKind,
+        header: usize,
+        count: usize,
+    },
 }
 
 impl Serializer {
     #[must_use]
     pub const fn new() -> Self {
-        Self { output: Vec::new() }
+        Self::with_writer(Vec::new())
+    }
+
+    /// Clears the output so the serializer (and its allocation) can be reused.
+    pub fn clear(&mut self) {
+        self.output.clear();
+        self.kind = None;
+        self.raw = false;
+        self.open.clear();
+        self.counted = 0;
+        self.buffer.clear();
     }
 
     /// Inspect the current output for debugging purposes.
@@ -21,9 +75,165 @@ impl Serializer {
     }
 }
 
+impl<W> Serializer<W>
+where
+    W: Write,
+{
+    /// Creates a serializer that writes into `writer`.
+    pub const fn with_writer(writer: W) -> Self {
+        Self {
+            output: writer,
+            kind: None,
+            raw: false,
+            open: Vec::new(),
+            count_lengths: false,
+            counted: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Counts the elements of sequences and maps of unknown length, and writes the count
+    /// in their header, instead of writing them as RESP3 streamed aggregates.
+    /// Useful for servers that don't support streamed aggregates, like RESP2 servers.
+    ///
+    /// Since the header comes first, such aggregates are kept in memory until they end.
+    ///
+    /// ```
+    /// # use rediserde::Serializer;
+    /// # use serde::Serializer as _;
+    /// let mut serializer = Serializer::new().count_unknown_lengths();
+    /// let odd = [1, 2, 3].into_iter().filter(|n| n % 2 == 1);
+    /// serializer.collect_seq(odd).unwrap();
+    /// assert_eq!(serializer.get_ref(), b"*2\r\n:1\r\n:3\r\n");
+    /// ```
+    #[must_use]
+    pub const fn count_unknown_lengths(mut self) -> Self {
+        self.count_lengths = true;
+        self
+    }
+
+    /// The writer of this serializer.
+    pub const fn get_ref(&self) -> &W {
+        &self.output
+    }
+
+    /// The writer of this serializer. Writing to it directly may produce invalid RESP data.
+    pub const fn get_mut(&mut self) -> &mut W {
+        &mut self.output
+    }
+
+    /// Consumes the serializer and returns its writer.
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+
+    /// Writes a bulk string with the next `len` bytes of `reader`, copying them as they're read
+    /// so large values never have to be in memory at once.
+    ///
+    /// Fails if `reader` ends before `len` bytes, in which case the output is left incomplete.
+    ///
+    /// ```
+    /// # use rediserde::Serializer;
+    /// # use serde::Serialize;
+    /// # use std::io::Write;
+    /// let blob = &b"Hello World"[..]; // e.g. a `File`
+    /// let mut serializer = Serializer::new();
+    /// serializer.get_mut().write_all(b"*3\r\n").unwrap();
+    /// "SET".serialize(&mut serializer).unwrap();
+    /// "key".serialize(&mut serializer).unwrap();
+    /// serializer.write_bulk_from_reader(blob.len(), blob).unwrap();
+    /// assert_eq!(serializer.get_ref(), b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$11\r\nHello World\r\n");
+    /// ```
+    pub fn write_bulk_from_reader<R>(&mut self, len: usize, reader: R) -> Result<()>
+    where
+        R: Read,
+    {
+        self.write(&[RespDataKind::BulkString.to_prefix_bytes()])?;
+        self.write(len.to_string().as_bytes())?;
+        self.write(CRLF)?;
+        let expected = u64::try_from(len).map_err(|_| Error::ExpectedLength)?;
+        let mut reader = reader.take(expected);
+        let copied = if self.counted > 0 {
+            std::io::copy(&mut reader, &mut self.buffer)?
+        } else {
+            std::io::copy(&mut reader, &mut self.output)?
+        };
+        if copied != expected {
+            return Err(Error::UnexpectedEnd);
+        }
+        self.write(CRLF)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        if self.counted > 0 {
+            self.buffer.extend_from_slice(data);
+            return Ok(());
+        }
+        Ok(self.output.write_all(data)?)
+    }
+
+    /// Writes the header of an aggregate. If the length is unknown, it's either a RESP3
+    /// streamed aggregate, or the header is written later (see `count_unknown_lengths`).
+    fn write_aggregate_header(&mut self, kind: RespDataKind, len: Option<usize>) -> Result<()> {
+        if len.is_none() && self.count_lengths {
+            self.counted += 1;
+            self.open.push(Aggregate::Counted {
+                kind,
+                header: self.buffer.len(),
+                count: 0,
+            });
+            return Ok(());
+        }
+        self.write(&[kind.to_prefix_bytes()])?;
+        match len {
+            Some(len) => self.write(len.to_string().as_bytes())?,
+            None => self.write(&[STREAMED])?,
+        }
+        self.write(CRLF)?;
+        self.open.push(if len.is_some() {
+            Aggregate::Known
+        } else {
+            Aggregate::Streamed
+        });
+        Ok(())
+    }
+
+    /// Counts the next element (or map entry) of the innermost open aggregate.
+    fn count_element(&mut self) {
+        if let Some(Aggregate::Counted { count, .. }) = self.open.last_mut() {
+            *count += 1;
+        }
+    }
+
+    /// Ends the innermost open aggregate, writing the end marker if it was streamed,
+    /// or the header if it was counted.
+    fn end_aggregate(&mut self) -> Result<()> {
+        match self.open.pop() {
+            Some(Aggregate::Streamed) => self.write(STREAMED_END)?,
+            Some(Aggregate::Counted {
+                kind,
+                header,
+                count,
+            }) => {
+                let mut data = vec![kind.to_prefix_bytes()];
+                data.extend_from_slice(count.to_string().as_bytes());
+                data.extend_from_slice(CRLF);
+                self.buffer.splice(header..header, data);
+                self.counted -= 1;
+                if self.counted == 0 {
+                    self.output.write_all(&self.buffer)?;
+                    self.buffer.clear();
+                }
+            }
+            Some(Aggregate::Known) | None => {}
+        }
+        Ok(())
+    }
+}
+
 pub fn to_bytes<T>(value: &T) -> Result<Vec<u8>>
 where
-    T: serde::Serialize,
+    T: ?Sized + serde::Serialize,
 {
     let mut serializer = Serializer::new();
     value.serialize(&mut serializer)?;
@@ -32,14 +242,34 @@ where
 
 pub fn to_string<T>(value: &T) -> Result<String>
 where
-    T: serde::Serialize,
+    T: ?Sized + serde::Serialize,
 {
     let mut serializer = Serializer::new();
     value.serialize(&mut serializer)?;
     Ok(String::from_utf8(serializer.output)?)
 }
 
-impl serde::Serializer for &mut Serializer {
+/// Serializes `value` into `writer`, which is not flushed.
+///
+/// ```
+/// # use rediserde::to_writer;
+/// let mut output = Vec::new();
+/// to_writer(&mut output, &["SET", "key", "value"]).unwrap();
+/// assert_eq!(output, b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n");
+/// ```
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: Write,
+    T: ?Sized + serde::Serialize,
+{
+    let mut serializer = Serializer::with_writer(writer);
+    value.serialize(&mut serializer)
+}
+
+impl<W> serde::Serializer for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
     type SerializeSeq = Self;
@@ -52,9 +282,9 @@ impl serde::Serializer for &mut Serializer {
 
     /// #<t|f>\r\n
     fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
-        self.output.push(RespDataKind::Boolean.to_prefix_bytes());
-        self.output.push(if v { b't' } else { b'f' });
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::Boolean.to_prefix_bytes()])?;
+        self.write(&[if v { b't' } else { b'f' }])?;
+        self.write(CRLF)?;
         Ok(())
     }
 
@@ -74,9 +304,9 @@ impl serde::Serializer for &mut Serializer {
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
-        self.output.push(RespDataKind::Integer.to_prefix_bytes());
-        self.output.extend_from_slice(v.to_string().as_bytes());
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::Integer.to_prefix_bytes()])?;
+        self.write(v.to_string().as_bytes())?;
+        self.write(CRLF)?;
         Ok(())
     }
 
@@ -97,24 +327,38 @@ impl serde::Serializer for &mut Serializer {
 
     /// RESP Integer is at most i64, so a u64 will be serialized as a `BigNumber`.
     fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
-        self.output.push(RespDataKind::BigNumber.to_prefix_bytes());
-        self.output.extend_from_slice(v.to_string().as_bytes());
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::BigNumber.to_prefix_bytes()])?;
+        self.write(v.to_string().as_bytes())?;
+        self.write(CRLF)?;
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok> {
+        self.write(&[RespDataKind::BigNumber.to_prefix_bytes()])?;
+        self.write(v.to_string().as_bytes())?;
+        self.write(CRLF)?;
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok> {
+        self.write(&[RespDataKind::BigNumber.to_prefix_bytes()])?;
+        self.write(v.to_string().as_bytes())?;
+        self.write(CRLF)?;
         Ok(())
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
         // Does *not* use `self.serialize_f64` internally to avoid precision loss.
-        self.output.push(RespDataKind::Float.to_prefix_bytes());
-        self.output.extend_from_slice(v.to_string().as_bytes());
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::Float.to_prefix_bytes()])?;
+        self.write(format_float(v).as_bytes())?;
+        self.write(CRLF)?;
         Ok(())
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
-        self.output.push(RespDataKind::Float.to_prefix_bytes());
-        self.output.extend_from_slice(v.to_string().as_bytes());
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::Float.to_prefix_bytes()])?;
+        self.write(format_float(v).as_bytes())?;
+        self.write(CRLF)?;
         Ok(())
     }
 
@@ -131,21 +375,47 @@ impl serde::Serializer for &mut Serializer {
     }
 
     /// Always serializes as a bulk string and not a simple string.
+    ///
+    /// Unless marked otherwise by [`crate::Value`], which may ask for any of the other string kinds.
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        if std::mem::take(&mut self.raw) {
+            self.write(v)?;
+            return Ok(());
+        }
+        match self.kind.take() {
+            Some(
+                kind @ (RespDataKind::SimpleString
+                | RespDataKind::SimpleError
+                | RespDataKind::BigNumber),
+            ) => {
+                // <prefix><data>\r\n
+                if v.contains(&b'\r') || v.contains(&b'\n') {
+                    return Err(Error::SerializeError(format!(
+                        "A {kind:?} cannot contain CR or LF characters"
+                    )));
+                }
+                self.write(&[kind.to_prefix_bytes()])?;
+                self.write(v)?;
+                self.write(CRLF)?;
+                return Ok(());
+            }
+            Some(kind @ (RespDataKind::BulkError | RespDataKind::VerbatimString)) => {
+                self.write(&[kind.to_prefix_bytes()])?;
+            }
+            _ => self.write(&[RespDataKind::BulkString.to_prefix_bytes()])?,
+        }
         // $<length>\r\n<data>\r\n
-        self.output.push(RespDataKind::BulkString.to_prefix_bytes());
-        self.output
-            .extend_from_slice(v.len().to_string().as_bytes());
-        self.output.extend_from_slice(CRLF);
-        self.output.extend_from_slice(v);
-        self.output.extend_from_slice(CRLF);
+        self.write(v.len().to_string().as_bytes())?;
+        self.write(CRLF)?;
+        self.write(v)?;
+        self.write(CRLF)?;
         Ok(())
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
         // _\r\n
-        // As this is known to be a constant, we avoid multiple push/extend calls.
-        self.output.extend_from_slice(b"_\r\n");
+        // As this is known to be a constant, we avoid multiple write calls.
+        self.write(b"_\r\n")?;
         Ok(())
     }
 
@@ -177,10 +447,15 @@ impl serde::Serializer for &mut Serializer {
     }
 
     /// Ignores the newtype wrapper, serializes the data directly
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    ///
+    /// Marker newtypes used by [`crate::Value`] set the RESP kind of the wrapped data,
+    /// and [`crate::RawResp`] marks its data to be written as-is.
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok>
     where
         T: ?Sized + serde::Serialize,
     {
+        self.kind = RespDataKind::from_token(name);
+        self.raw = name == crate::raw::TOKEN;
         value.serialize(self)
     }
 
@@ -195,28 +470,25 @@ impl serde::Serializer for &mut Serializer {
     where
         T: ?Sized + serde::Serialize,
     {
-        self.output.push(RespDataKind::Map.to_prefix_bytes());
-        self.output.push(b'1'); // Single key-value pair
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::Map.to_prefix_bytes()])?;
+        self.write(b"1")?; // Single key-value pair
+        self.write(CRLF)?;
         self.serialize_str(variant)?;
         value.serialize(self)
     }
 
     /// Serializes a sequence as an array.
     /// An empty sequence is serialized as *0\r\n
-    /// A null sequence is serialized as *-1\r\n, and will be output for a sequence of unknown length.
     /// A non-empty sequence is serialized as `*<length>\r\n<data>`
+    /// A sequence of unknown length is serialized as a streamed array: `*?\r\n<data>.\r\n`
+    ///
+    /// Sets and pushes may only be requested by [`crate::Value`].
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
-        self.output.push(RespDataKind::Array.to_prefix_bytes());
-        match len {
-            Some(l) => {
-                self.output.extend_from_slice(l.to_string().as_bytes());
-            }
-            None => {
-                self.output.extend_from_slice(b"-1");
-            }
-        }
-        self.output.extend_from_slice(CRLF);
+        let kind = match self.kind.take() {
+            Some(kind @ (RespDataKind::Set | RespDataKind::Push)) => kind,
+            _ => RespDataKind::Array,
+        };
+        self.write_aggregate_header(kind, len)?;
         Ok(self)
     }
 
@@ -244,21 +516,23 @@ impl serde::Serializer for &mut Serializer {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.output.push(RespDataKind::Map.to_prefix_bytes());
-        self.output.extend_from_slice(b"1"); // Single key-value pair
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::Map.to_prefix_bytes()])?;
+        self.write(b"1")?; // Single key-value pair
+        self.write(CRLF)?;
         self.serialize_str(variant)?;
         self.serialize_seq(Some(len))
     }
 
+    /// A map of unknown length is serialized as a streamed map: `%?\r\n<data>.\r\n`
+    ///
+    /// Attributes may only be requested by [`crate::Value`].
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
         // %<number-of-entries>\r\n<key-1><value-1>...<key-n><value-n>
-        let len = len.ok_or_else(|| {
-            Error::SerializeError("Cannot serialize a map with unknown length".to_string())
-        })?;
-        self.output.push(RespDataKind::Map.to_prefix_bytes());
-        self.output.extend_from_slice(len.to_string().as_bytes());
-        self.output.extend_from_slice(CRLF);
+        let kind = match self.kind.take() {
+            Some(RespDataKind::Attributes) => RespDataKind::Attributes,
+            _ => RespDataKind::Map,
+        };
+        self.write_aggregate_header(kind, len)?;
         Ok(self)
     }
 
@@ -277,15 +551,18 @@ impl serde::Serializer for &mut Serializer {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.output.push(RespDataKind::Map.to_prefix_bytes());
-        self.output.extend_from_slice(b"1"); // Single key-value pair
-        self.output.extend_from_slice(CRLF);
+        self.write(&[RespDataKind::Map.to_prefix_bytes()])?;
+        self.write(b"1")?; // Single key-value pair
+        self.write(CRLF)?;
         self.serialize_str(variant)?;
         self.serialize_struct(name, len)
     }
 }
 
-impl serde::ser::SerializeSeq for &mut Serializer {
+impl<W> serde::ser::SerializeSeq for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
 
@@ -295,16 +572,20 @@ impl serde::ser::SerializeSeq for &mut Serializer {
     where
         T: ?Sized + serde::Serialize,
     {
+        self.count_element();
         value.serialize(&mut **self)
     }
 
-    /// There is no ending output to a RESP array, adds nothing
+    /// There is no ending output to a RESP array, unless it's streamed
     fn end(self) -> Result<Self::Ok> {
-        Ok(())
+        self.end_aggregate()
     }
 }
 
-impl serde::ser::SerializeTuple for &mut Serializer {
+impl<W> serde::ser::SerializeTuple for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
 
@@ -322,7 +603,10 @@ impl serde::ser::SerializeTuple for &mut Serializer {
     }
 }
 
-impl serde::ser::SerializeTupleStruct for &mut Serializer {
+impl<W> serde::ser::SerializeTupleStruct for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
 
@@ -340,7 +624,10 @@ impl serde::ser::SerializeTupleStruct for &mut Serializer {
     }
 }
 
-impl serde::ser::SerializeTupleVariant for &mut Serializer {
+impl<W> serde::ser::SerializeTupleVariant for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
 
@@ -358,7 +645,10 @@ impl serde::ser::SerializeTupleVariant for &mut Serializer {
     }
 }
 
-impl serde::ser::SerializeMap for &mut Serializer {
+impl<W> serde::ser::SerializeMap for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
 
@@ -367,6 +657,7 @@ impl serde::ser::SerializeMap for &mut Serializer {
     where
         T: ?Sized + serde::Serialize,
     {
+        self.count_element();
         key.serialize(&mut **self)
     }
 
@@ -378,13 +669,16 @@ impl serde::ser::SerializeMap for &mut Serializer {
         value.serialize(&mut **self)
     }
 
-    /// There is no ending output to a RESP map, adds nothing
+    /// There is no ending output to a RESP map, unless it's streamed
     fn end(self) -> Result<Self::Ok> {
-        Ok(())
+        self.end_aggregate()
     }
 }
 
-impl serde::ser::SerializeStruct for &mut Serializer {
+impl<W> serde::ser::SerializeStruct for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
 
@@ -404,7 +698,10 @@ impl serde::ser::SerializeStruct for &mut Serializer {
     }
 }
 
-impl serde::ser::SerializeStructVariant for &mut Serializer {
+impl<W> serde::ser::SerializeStructVariant for &mut Serializer<W>
+where
+    W: Write,
+{
     type Ok = ();
     type Error = Error;
 
@@ -430,6 +727,19 @@ mod tests {
     use serde::Serialize;
     use std::collections::HashMap;
 
+    /// The odd numbers in a list, a sequence with an unknown length.
+    struct Odd(Vec<u8>);
+
+    impl Serialize for Odd {
+        fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            // `Filter` has no exact size hint, so the length is unknown
+            serializer.collect_seq(self.0.iter().filter(|n| *n % 2 == 1))
+        }
+    }
+
     fn test_u(val: u8, expected: &str) {
         assert_eq!(to_string(&val).unwrap(), expected, "u8");
         assert_eq!(to_string(&(val as u16)).unwrap(), expected, "u16");
@@ -462,6 +772,16 @@ mod tests {
         let val = 12345678901234567890_u64;
         assert_eq!(to_string(&val).unwrap(), expected);
         assert_eq!(to_string(&(val as usize)).unwrap(), expected, "usize");
+        assert_eq!(to_string(&(val as u128)).unwrap(), expected, "u128");
+        assert_eq!(to_string(&(val as i128)).unwrap(), expected, "i128");
+        assert_eq!(
+            to_string(&i128::MIN).unwrap(),
+            "(-170141183460469231731687303715884105728\r\n"
+        );
+        assert_eq!(
+            to_string(&u128::MAX).unwrap(),
+            "(340282366920938463463374607431768211455\r\n"
+        );
     }
 
     #[test]
@@ -470,16 +790,25 @@ mod tests {
         assert_eq!(to_string(&3.1_f64).unwrap(), ",3.1\r\n", "plus f64");
         assert_eq!(to_string(&-3.1_f32).unwrap(), ",-3.1\r\n", "plus f32");
         assert_eq!(to_string(&-3.1_f64).unwrap(), ",-3.1\r\n", "plus f64");
-        assert_eq!(
-            to_string(&2e20_f64).unwrap(),
-            ",200000000000000000000\r\n",
-            "exp f64"
-        );
-        assert_eq!(
-            to_string(&2e-20_f64).unwrap(),
-            ",0.00000000000000000002\r\n",
-            "neg exp f64"
-        );
+        assert_eq!(to_string(&2e20_f64).unwrap(), ",2e20\r\n", "exp f64");
+        assert_eq!(to_string(&2e-20_f64).unwrap(), ",2e-20\r\n", "neg exp f64");
+        assert_eq!(to_string(&1.5e-7_f32).unwrap(), ",1.5e-7\r\n", "exp f32");
+        assert_eq!(to_string(&100.0_f64).unwrap(), ",100\r\n", "plain on a tie");
+        assert_eq!(to_string(&f64::INFINITY).unwrap(), ",inf\r\n");
+        assert_eq!(to_string(&f32::NEG_INFINITY).unwrap(), ",-inf\r\n");
+        assert_eq!(to_string(&f64::NAN).unwrap(), ",nan\r\n");
+        for v in [
+            0.1,
+            -0.0,
+            1.0 / 3.0,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+            123_456.789,
+        ] {
+            let round_trip: f64 = crate::from_str(&to_string(&v).unwrap()).unwrap();
+            assert_eq!(round_trip.to_bits(), v.to_bits(), "{v}");
+        }
     }
 
     #[test]
@@ -522,6 +851,66 @@ mod tests {
         assert!(out == expected1 || out == expected2);
     }
 
+    #[test]
+    fn test_streamed() {
+        let odd = vec![Odd(vec![1, 2, 3]), Odd(vec![])];
+        let expected = "*2\r\n*?\r\n:1\r\n:3\r\n.\r\n*0\r\n"; // Empty has an exact hint
+        assert_eq!(to_string(&odd).unwrap(), expected);
+        let parsed: Vec<Vec<u8>> = crate::from_str(expected).unwrap();
+        assert_eq!(parsed, vec![vec![1, 3], vec![]]);
+
+        #[derive(Serialize)]
+        struct Inner {
+            b: u8,
+        }
+
+        #[derive(Serialize)]
+        struct Outer {
+            a: u8,
+            #[serde(flatten)]
+            inner: Inner,
+            c: (u8,),
+        }
+
+        let outer = Outer {
+            a: 1,
+            inner: Inner { b: 2 },
+            c: (3,),
+        };
+        let expected = "%?\r\n$1\r\na\r\n:1\r\n$1\r\nb\r\n:2\r\n$1\r\nc\r\n*1\r\n:3\r\n.\r\n";
+        assert_eq!(to_string(&outer).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_counted() {
+        #[derive(Serialize)]
+        struct Flat {
+            #[serde(flatten)]
+            inner: HashMap<String, Odd>,
+        }
+
+        let value = Flat {
+            inner: [("a".to_string(), Odd(vec![1, 2, 3, 5]))].into(),
+        };
+        let mut serializer = Serializer::with_writer(Vec::new()).count_unknown_lengths();
+        (&value, &value).serialize(&mut serializer).unwrap();
+        let flat = "%1\r\n$1\r\na\r\n*3\r\n:1\r\n:3\r\n:5\r\n";
+        let expected = format!("*2\r\n{flat}{flat}");
+        assert_eq!(serializer.get_ref(), expected.as_bytes());
+        let parsed: Vec<HashMap<String, Vec<u8>>> =
+            crate::from_bytes(serializer.get_ref()).unwrap();
+        assert_eq!(parsed[1]["a"], vec![1, 3, 5]);
+
+        // The buffered output is written when the aggregate ends
+        let mut serializer =
+            Serializer::with_writer(std::io::Cursor::new([0; 8])).count_unknown_lengths();
+        assert!(matches!(
+            Odd(vec![1; 4]).serialize(&mut serializer),
+            Err(Error::Io(_))
+        ));
+        assert_eq!(&serializer.into_inner().into_inner(), b"*4\r\n:1\r\n");
+    }
+
     #[test]
     fn test_struct() {
         #[derive(Serialize, PartialEq, Debug)]
@@ -558,6 +947,37 @@ mod tests {
         assert!(out == expected1 || out == expected2 || out == expected3);
     }
 
+    #[test]
+    fn test_bulk_from_reader() {
+        let mut serializer = Serializer::new();
+        serializer
+            .write_bulk_from_reader(5, &b"Hello World"[..])
+            .unwrap();
+        serializer.write_bulk_from_reader(0, &b""[..]).unwrap();
+        assert_eq!(serializer.get_ref(), b"$5\r\nHello\r\n$0\r\n\r\n");
+
+        let mut serializer = Serializer::new();
+        assert!(matches!(
+            serializer.write_bulk_from_reader(5, &b"Hi"[..]),
+            Err(Error::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut output = b"prefix".to_vec();
+        to_writer(&mut output, &1).unwrap();
+        to_writer(&mut output, &"a").unwrap();
+        assert_eq!(output, b"prefix:1\r\n$1\r\na\r\n");
+
+        let mut serializer = Serializer::with_writer(std::io::Cursor::new([0; 4]));
+        assert!(matches!(
+            "too long".serialize(&mut serializer),
+            Err(Error::Io(_))
+        ));
+        assert_eq!(&serializer.into_inner().into_inner(), b"$8\r\n");
+    }
+
     #[test]
     fn test_enum() {
         #[derive(Serialize, PartialEq, Debug)]
@@ -0,0 +1,186 @@
+//! Zero-copy [`Bytes`] fields, for use with `#[serde(with = "rediserde::shared_bytes")]`.
+//!
+//! When deserializing with [`from_shared_bytes`], the payloads of such fields share the
+//! allocation of the input buffer instead of being copied. With any other deserializer
+//! (or a payload that had to be joined, like a streamed string) the data is copied as usual.
+//!
+//! ```
+//! # use bytes::Bytes;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Entry {
+//!     key: String,
+//!     #[serde(with = "rediserde::shared_bytes")]
+//!     value: Bytes,
+//! }
+//!
+//! let input = Bytes::from_static(b"%2\r\n+key\r\n$1\r\na\r\n+value\r\n$5\r\nHello\r\n");
+//! let entry: Entry = rediserde::from_shared_bytes(&input).unwrap();
+//! assert_eq!(entry.value, "Hello");
+//! assert!(input.as_ptr_range().contains(&entry.value.as_ptr()), "Shares the input");
+//! ```
+
+use crate::{Result, Serializer};
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+thread_local! {
+    /// The input of the current `from_shared_bytes` call, which `Bytes` fields can share.
+    static SOURCE: RefCell<Option<Bytes>> = const { RefCell::new(None) };
+}
+
+/// Deserializes `input` like [`crate::from_bytes`], but fields using [this module](self)
+/// share the allocation of `input` instead of copying their payloads.
+pub fn from_shared_bytes<'de, T>(input: &'de Bytes) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    /// Restores the previous source, even if deserializing panics.
+    struct Restore(Option<Bytes>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SOURCE.set(self.0.take());
+        }
+    }
+
+    let _restore = Restore(SOURCE.replace(Some(input.clone())));
+    crate::from_bytes(input)
+}
+
+/// Serializes `value` to the end of `output`.
+///
+/// ```
+/// # use bytes::BytesMut;
+/// let mut output = BytesMut::new();
+/// rediserde::to_bytes_mut(&mut output, &["GET", "key"]).unwrap();
+/// assert_eq!(&output[..], b"*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n");
+/// ```
+pub fn to_bytes_mut<T>(output: &mut BytesMut, value: &T) -> Result<()>
+where
+    T: ?Sized + Serialize,
+{
+    let mut serializer = Serializer::with_writer(output.writer());
+    value.serialize(&mut serializer)
+}
+
+/// Serializes the bytes as a bulk string.
+pub fn serialize<S>(value: &Bytes, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_bytes(value)
+}
+
+/// Deserializes any string kind, sharing the input of [`from_shared_bytes`] if possible.
+pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Bytes, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(BytesVisitor)
+}
+
+struct BytesVisitor;
+
+impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("bytes")
+    }
+
+    fn visit_borrowed_bytes<E: serde::de::Error>(
+        self,
+        v: &'de [u8],
+    ) -> std::result::Result<Self::Value, E> {
+        let shared = SOURCE.with_borrow(|source| {
+            let source = source.as_ref()?;
+            let range = source.as_ptr_range();
+            let inside = range.start <= v.as_ptr() && v.as_ptr_range().end <= range.end;
+            (inside && !v.is_empty()).then(|| source.slice_ref(v))
+        });
+        Ok(shared.unwrap_or_else(|| Bytes::copy_from_slice(v)))
+    }
+
+    fn visit_borrowed_str<E: serde::de::Error>(
+        self,
+        v: &'de str,
+    ) -> std::result::Result<Self::Value, E> {
+        self.visit_borrowed_bytes(v.as_bytes())
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> std::result::Result<Self::Value, E> {
+        Ok(Bytes::copy_from_slice(v))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
+        Ok(Bytes::copy_from_slice(v.as_bytes()))
+    }
+
+    fn visit_byte_buf<E: serde::de::Error>(
+        self,
+        v: Vec<u8>,
+    ) -> std::result::Result<Self::Value, E> {
+        Ok(Bytes::from(v))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, v: String) -> std::result::Result<Self::Value, E> {
+        Ok(Bytes::from(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Entry {
+        #[serde(with = "super")]
+        value: Bytes,
+    }
+
+    fn is_shared(input: &Bytes, value: &Bytes) -> bool {
+        input.as_ptr_range().contains(&value.as_ptr())
+    }
+
+    #[test]
+    fn test_shared() {
+        let input = Bytes::from(
+            b"*2\r\n%1\r\n+value\r\n$5\r\nHello\r\n%1\r\n+value\r\n+World\r\n".to_vec(),
+        );
+        let entries: Vec<Entry> = from_shared_bytes(&input).unwrap();
+        assert_eq!(entries[0].value, "Hello");
+        assert_eq!(entries[1].value, "World");
+        assert!(entries.iter().all(|entry| is_shared(&input, &entry.value)));
+        assert!(
+            SOURCE.with_borrow(Option::is_none),
+            "Only set while deserializing"
+        );
+    }
+
+    #[test]
+    fn test_copied() {
+        let input = Bytes::from(b"%1\r\n+value\r\n$?\r\n;2\r\nab\r\n;0\r\n".to_vec());
+        let entry: Entry = from_shared_bytes(&input).unwrap();
+        assert_eq!(entry.value, "ab");
+        assert!(
+            !is_shared(&input, &entry.value),
+            "Streamed strings are joined"
+        );
+
+        let input = b"%1\r\n+value\r\n$2\r\nab\r\n";
+        let entry: Entry = crate::from_bytes(input).unwrap();
+        assert_eq!(entry.value, "ab");
+    }
+
+    #[test]
+    fn test_bytes_mut() {
+        let mut output = BytesMut::from(&b"prefix"[..]);
+        let entry = Entry {
+            value: Bytes::from_static(b"ab"),
+        };
+        to_bytes_mut(&mut output, &entry).unwrap();
+        assert_eq!(&output[..], b"prefix%1\r\n$5\r\nvalue\r\n$2\r\nab\r\n");
+    }
+}
@@ -0,0 +1,86 @@
+//! Reading and writing RESP data with tokio's [`AsyncRead`] and [`AsyncWrite`].
+//!
+//! The async counterparts of [`crate::from_reader`] and [`crate::to_writer`], for one-shot
+//! requests and responses without a framed transport.
+
+use crate::Result;
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Reads exactly one complete RESP value from `reader` and deserializes it.
+///
+/// Nothing after the value is read, so the same reader can be used for the next value.
+/// Headers are read one byte at a time, so wrapping unbuffered readers in a `BufReader`
+/// is recommended, passing `&mut` to keep using it.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut reader = &b"*2\r\n:1\r\n:2\r\n+OK\r\n"[..];
+/// let numbers: Vec<u32> = rediserde::tokio::from_async_reader(&mut reader).await.unwrap();
+/// assert_eq!(numbers, vec![1, 2]);
+/// assert_eq!(reader, b"+OK\r\n");
+/// # })
+/// ```
+pub async fn from_async_reader<R, T>(mut reader: R) -> Result<T>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut frame = Vec::new();
+    read_frame(&mut reader, &mut frame).await?;
+    crate::from_bytes(&frame)
+}
+
+/// Serializes `value` and writes it to `writer`, which is not flushed.
+///
+/// The value is serialized in memory first, and then written all at once.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut output = Vec::new();
+/// rediserde::tokio::to_async_writer(&mut output, &["GET", "key"]).await.unwrap();
+/// assert_eq!(output, b"*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n");
+/// # })
+/// ```
+pub async fn to_async_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: ?Sized + Serialize,
+{
+    let output = crate::to_bytes(value)?;
+    writer.write_all(&output).await?;
+    Ok(())
+}
+
+crate::read::impl_read_frame!(async, [.await], AsyncRead + Unpin);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Value};
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let value = Value::Array(vec![
+            Value::BulkString(b"\r\n".to_vec()),
+            Value::Map(vec![(Value::Integer(1), Value::Null)]),
+        ]);
+        let mut output = Vec::new();
+        to_async_writer(&mut output, &value).await.unwrap();
+        to_async_writer(&mut output, &1).await.unwrap();
+        output.extend_from_slice(b"*?\r\n$?\r\n;1\r\na\r\n;0\r\n.\r\n");
+        let mut reader = &output[..];
+        assert_eq!(
+            from_async_reader::<_, Value>(&mut reader).await.unwrap(),
+            value
+        );
+        assert_eq!(from_async_reader::<_, u8>(&mut reader).await.unwrap(), 1);
+        let streamed: Vec<String> = from_async_reader(&mut reader).await.unwrap();
+        assert_eq!(streamed, ["a"]);
+        assert!(reader.is_empty());
+        assert!(matches!(
+            from_async_reader::<_, Value>(&b"$5\r\nab"[..]).await,
+            Err(Error::UnexpectedEnd)
+        ));
+    }
+}
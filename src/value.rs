@@ -0,0 +1,748 @@
+mod borrowed;
+mod de;
+mod index;
+mod ser;
+
+use crate::{BigNumber, RespDataKind};
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use std::marker::PhantomData;
+
+pub use borrowed::ValueRef;
+pub use de::from_value;
+pub use index::ValueIndex;
+pub use ser::to_value;
+
+/// Name of the newtype struct [`Value`] asks for when deserializing.
+///
+/// [`crate::Deserializer`] answers it with the kind of the next RESP value as an
+/// enum variant, so nothing is lost between kinds that look the same to serde.
+pub(crate) const TOKEN: &str = "$rediserde::Value";
+
+/// Implements `Serialize` and `Deserialize` for [`Value`] and [`ValueRef`], which only
+/// differ in how their data is stored.
+///
+/// The visitor handles everything but plain strings and bytes, which are left to the
+/// methods given in the last block (owned or borrowed). `bulk`, `verbatim` and `big` are the
+/// types the data of bulk strings, verbatim strings and big numbers are deserialized to,
+/// before being converted with `into`.
+macro_rules! impl_value_serde {
+    (
+        $value:ident $(<$lt:lifetime>)?, $visitor:ident, $expecting:literal,
+        bulk: $bulk:ty,
+        verbatim: $verbatim:ty,
+        big: $big:ty,
+        { $($methods:tt)* }
+    ) => {
+        impl$(<$lt>)? serde::Serialize for $value$(<$lt>)? {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                use $crate::value::{Entries, join_verbatim};
+
+                let token = self.kind().to_token();
+                match self {
+                    Self::SimpleString(s) | Self::SimpleError(s) | Self::BulkError(s) => {
+                        serializer.serialize_newtype_struct(token, s)
+                    }
+                    Self::BigNumber(n) => serializer.serialize_newtype_struct(token, AsRef::<str>::as_ref(n)),
+                    Self::Integer(i) => serializer.serialize_i64(*i),
+                    Self::BulkString(b) => serializer.serialize_bytes(b),
+                    Self::Array(items) => serializer.collect_seq(items),
+                    Self::Null => serializer.serialize_unit(),
+                    Self::Boolean(b) => serializer.serialize_bool(*b),
+                    Self::Float(f) => serializer.serialize_f64(*f),
+                    Self::VerbatimString { encoding, text } => {
+                        serializer.serialize_newtype_struct(token, &join_verbatim(encoding, text)?)
+                    }
+                    Self::Map(entries) => Entries(entries).serialize(serializer),
+                    Self::Attributes(entries) => {
+                        serializer.serialize_newtype_struct(token, &Entries(entries))
+                    }
+                    Self::Set(items) | Self::Push(items) => {
+                        serializer.serialize_newtype_struct(token, items)
+                    }
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $value$(<$lt>)? {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_newtype_struct($crate::value::TOKEN, $visitor)
+            }
+        }
+
+        struct $visitor;
+
+        impl<'de> serde::de::Visitor<'de> for $visitor {
+            type Value = $value$(<$lt>)?;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str($expecting)
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+                Ok($value::Boolean(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+                Ok($value::Integer(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+                Ok($value::Float(v))
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E> {
+                Ok($value::Null)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                serde::Deserialize::deserialize(deserializer)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok($value::Null)
+            }
+
+            // Other formats don't know about `TOKEN` and just hand over the inner data.
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok($value::Array(items))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let entries = $crate::value::EntriesVisitor(std::marker::PhantomData);
+                serde::de::Visitor::visit_map(entries, map).map($value::Map)
+            }
+
+            // Only `crate::Deserializer` gets here, with the RESP kind as the variant.
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::EnumAccess<'de>,
+            {
+                use $crate::RespDataKind;
+                use $crate::value::{EntriesBuf, Kind, split_verbatim};
+                use serde::de::VariantAccess;
+
+                let (Kind(kind), variant) = data.variant()?;
+                let value = match kind {
+                    RespDataKind::SimpleString => $value::SimpleString(variant.newtype_variant()?),
+                    RespDataKind::SimpleError => $value::SimpleError(variant.newtype_variant()?),
+                    RespDataKind::Integer => $value::Integer(variant.newtype_variant()?),
+                    RespDataKind::BulkString => {
+                        $value::BulkString(variant.newtype_variant::<$bulk>()?.into())
+                    }
+                    RespDataKind::Array => $value::Array(variant.newtype_variant()?),
+                    RespDataKind::Null => {
+                        variant.unit_variant()?;
+                        $value::Null
+                    }
+                    RespDataKind::Boolean => $value::Boolean(variant.newtype_variant()?),
+                    RespDataKind::Float => $value::Float(variant.newtype_variant()?),
+                    RespDataKind::BigNumber => {
+                        $value::BigNumber(variant.newtype_variant::<$big>()?.into())
+                    }
+                    RespDataKind::BulkError => $value::BulkError(variant.newtype_variant()?),
+                    RespDataKind::VerbatimString => {
+                        let data: $verbatim = variant.newtype_variant()?;
+                        let (encoding, text) = split_verbatim(&*data)?;
+                        $value::VerbatimString {
+                            encoding: encoding.into(),
+                            text: text.into(),
+                        }
+                    }
+                    RespDataKind::Map => $value::Map(variant.newtype_variant::<EntriesBuf<_>>()?.0),
+                    RespDataKind::Attributes => {
+                        $value::Attributes(variant.newtype_variant::<EntriesBuf<_>>()?.0)
+                    }
+                    RespDataKind::Set => $value::Set(variant.newtype_variant()?),
+                    RespDataKind::Push => $value::Push(variant.newtype_variant()?),
+                };
+                Ok(value)
+            }
+
+            $($methods)*
+        }
+    };
+}
+
+pub(crate) use impl_value_serde;
+
+/// Any RESP value, with one variant for every [`RespDataKind`].
+///
+/// Useful when the shape of the data is not known ahead of time, or when it can't be
+/// represented by regular Rust types (like arrays or maps with mixed types).
+///
+/// Unlike other types, the exact RESP kind is kept when deserializing with this crate,
+/// so serializing the value again produces the same RESP data, byte-for-byte for strings,
+/// big numbers and aggregates of known length. The exceptions are integers and doubles
+/// that are not written the way this crate writes them, RESP2 nulls and streamed values,
+/// see the notes below.
+///
+/// ```
+/// # use rediserde::{Value, from_str, to_string};
+/// let raw = "*3\r\n+OK\r\n:42\r\n%1\r\n$4\r\nname\r\n~1\r\n#t\r\n";
+/// let value: Value = from_str(raw).unwrap();
+/// assert_eq!(
+///     value,
+///     Value::Array(vec![
+///         Value::SimpleString("OK".to_string()),
+///         Value::Integer(42),
+///         Value::Map(vec![(
+///             Value::BulkString(b"name".to_vec()),
+///             Value::Set(vec![Value::Boolean(true)]),
+///         )]),
+///     ])
+/// );
+/// assert_eq!(to_string(&value).unwrap(), raw);
+/// ```
+///
+/// A few notes on the representation:
+/// - Bulk strings are binary safe, so they are kept as bytes. All other string kinds
+///   must be UTF-8 encoded, as is the case for the rest of this crate.
+/// - Integers and doubles are kept as numbers, so they are written back in their shortest
+///   form: `:+5\r\n` becomes `:5\r\n`, `,1.50\r\n` becomes `,1.5\r\n` and `,1e2\r\n`
+///   becomes `,100\r\n`.
+/// - The RESP2 null bulk string (`$-1\r\n`) and null array (`*-1\r\n`) are read as
+///   [`Value::Null`], and written back as a RESP3 null (`_\r\n`), so they don't
+///   round-trip byte-for-byte.
+/// - Streamed strings and aggregates are read as regular values, with the chunks of
+///   strings joined, and written back with their length, so they don't round-trip
+///   byte-for-byte either.
+/// - Maps and attributes are kept as a list of pairs, which preserves their order
+///   and allows keys of any kind.
+/// - Attributes are a standalone value, the same way [`RespDataKind::Attributes`]
+///   is treated by the rest of the crate.
+///
+/// With other serde formats, the value maps to the closest serde type: all strings
+/// serialize as strings (bulk strings as bytes), sets and pushes as sequences, etc.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// See [`RespDataKind::SimpleString`]
+    SimpleString(String),
+    /// See [`RespDataKind::SimpleError`]
+    SimpleError(String),
+    /// See [`RespDataKind::Integer`]
+    Integer(i64),
+    /// See [`RespDataKind::BulkString`]
+    BulkString(Vec<u8>),
+    /// See [`RespDataKind::Array`]
+    Array(Vec<Value>),
+    /// See [`RespDataKind::Null`]
+    Null,
+    /// See [`RespDataKind::Boolean`]
+    Boolean(bool),
+    /// See [`RespDataKind::Float`]
+    Float(f64),
+    /// See [`RespDataKind::BigNumber`]
+    BigNumber(BigNumber),
+    /// See [`RespDataKind::BulkError`]
+    BulkError(String),
+    /// See [`RespDataKind::VerbatimString`]
+    VerbatimString {
+        /// Exactly 3 characters, for example `txt` or `mkd`
+        encoding: String,
+        text: String,
+    },
+    /// See [`RespDataKind::Map`]
+    Map(Vec<(Value, Value)>),
+    /// See [`RespDataKind::Attributes`]
+    Attributes(Vec<(Value, Value)>),
+    /// See [`RespDataKind::Set`]
+    Set(Vec<Value>),
+    /// See [`RespDataKind::Push`]
+    Push(Vec<Value>),
+}
+
+impl Value {
+    /// The RESP kind of this value.
+    #[must_use]
+    pub const fn kind(&self) -> RespDataKind {
+        match self {
+            Self::SimpleString(_) => RespDataKind::SimpleString,
+            Self::SimpleError(_) => RespDataKind::SimpleError,
+            Self::Integer(_) => RespDataKind::Integer,
+            Self::BulkString(_) => RespDataKind::BulkString,
+            Self::Array(_) => RespDataKind::Array,
+            Self::Null => RespDataKind::Null,
+            Self::Boolean(_) => RespDataKind::Boolean,
+            Self::Float(_) => RespDataKind::Float,
+            Self::BigNumber(_) => RespDataKind::BigNumber,
+            Self::BulkError(_) => RespDataKind::BulkError,
+            Self::VerbatimString { .. } => RespDataKind::VerbatimString,
+            Self::Map(_) => RespDataKind::Map,
+            Self::Attributes(_) => RespDataKind::Attributes,
+            Self::Set(_) => RespDataKind::Set,
+            Self::Push(_) => RespDataKind::Push,
+        }
+    }
+
+    /// Whether this is a [`Value::Null`].
+    #[must_use]
+    pub const fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// The value of a [`Value::Boolean`].
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// The value of a [`Value::Integer`], or of a [`Value::BigNumber`] that fits in an `i64`.
+    #[must_use]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            Self::BigNumber(n) => n.as_str().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The value of a [`Value::Integer`] or a [`Value::BigNumber`] that fits in a `u64`.
+    #[must_use]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Integer(i) => u64::try_from(*i).ok(),
+            Self::BigNumber(n) => n.as_str().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The value of a [`Value::Float`] or a [`Value::Integer`].
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(f) => Some(*f),
+            Self::Integer(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    /// The text of a simple, bulk or verbatim string. Bulk strings must be valid UTF-8.
+    ///
+    /// Errors are not strings, see [`Value::as_error`].
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::SimpleString(s) => Some(s),
+            Self::VerbatimString { text, .. } => Some(text),
+            Self::BulkString(b) => str::from_utf8(b).ok(),
+            _ => None,
+        }
+    }
+
+    /// The message of a simple or bulk error.
+    #[must_use]
+    pub fn as_error(&self) -> Option<&str> {
+        match self {
+            Self::SimpleError(s) | Self::BulkError(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The data of a simple, bulk or verbatim string.
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::BulkString(b) => Some(b),
+            _ => self.as_str().map(str::as_bytes),
+        }
+    }
+
+    /// The elements of an array, set or push.
+    #[must_use]
+    pub fn as_array(&self) -> Option<&[Self]> {
+        match self {
+            Self::Array(items) | Self::Set(items) | Self::Push(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// The entries of a map or attributes, which may have keys of any kind.
+    #[must_use]
+    pub fn as_map(&self) -> Option<&[(Self, Self)]> {
+        match self {
+            Self::Map(entries) | Self::Attributes(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+impl_value_serde! {
+    Value, ValueVisitor, "any RESP value",
+    bulk: ByteBuf,
+    verbatim: String,
+    big: BigNumber,
+    {
+        /// Anything that doesn't fit a RESP integer becomes a big number.
+        fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+            Ok(i64::try_from(v).map_or_else(|_| Value::BigNumber(v.into()), Value::Integer))
+        }
+
+        fn visit_i128<E>(self, v: i128) -> Result<Value, E> {
+            Ok(i64::try_from(v).map_or_else(|_| Value::BigNumber(v.into()), Value::Integer))
+        }
+
+        fn visit_u128<E>(self, v: u128) -> Result<Value, E> {
+            Ok(i64::try_from(v).map_or_else(|_| Value::BigNumber(v.into()), Value::Integer))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+            Ok(Value::BulkString(v.as_bytes().to_vec()))
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Value, E> {
+            Ok(Value::BulkString(v.into_bytes()))
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+            Ok(Value::BulkString(v.to_vec()))
+        }
+
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Value, E> {
+            Ok(Value::BulkString(v))
+        }
+    }
+}
+
+/// Joins the encoding and text of a verbatim string into its data.
+pub(crate) fn join_verbatim<E>(encoding: &str, text: &str) -> Result<String, E>
+where
+    E: serde::ser::Error,
+{
+    if encoding.len() != 3 {
+        return Err(E::custom(
+            "Verbatim string encoding must be exactly 3 bytes long",
+        ));
+    }
+    Ok(format!("{encoding}:{text}"))
+}
+
+/// Splits the data of a verbatim string into its encoding and text.
+pub(crate) fn split_verbatim<E>(data: &str) -> Result<(&str, &str), E>
+where
+    E: serde::de::Error,
+{
+    match (data.get(..3), data.get(3..4), data.get(4..)) {
+        (Some(encoding), Some(":"), Some(text)) => Ok((encoding, text)),
+        _ => Err(E::invalid_value(
+            serde::de::Unexpected::Str(data),
+            &"a verbatim string starting with a 3 bytes encoding and `:`",
+        )),
+    }
+}
+
+/// Serializes a list of pairs as a map.
+struct Entries<'a, V>(&'a [(V, V)]);
+
+impl<V> serde::Serialize for Entries<'_, V>
+where
+    V: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Deserializes a map as a list of pairs.
+struct EntriesBuf<V>(Vec<(V, V)>);
+
+impl<'de, V> serde::Deserialize<'de> for EntriesBuf<V>
+where
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_map(EntriesVisitor(PhantomData))
+            .map(EntriesBuf)
+    }
+}
+
+struct EntriesVisitor<V>(PhantomData<V>);
+
+impl<'de, V> Visitor<'de> for EntriesVisitor<V>
+where
+    V: serde::Deserialize<'de>,
+{
+    type Value = Vec<(V, V)>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a RESP map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+/// Deserializes any string as bytes, without requiring UTF-8.
+struct ByteBuf(Vec<u8>);
+
+impl<'de> serde::Deserialize<'de> for ByteBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ByteBufVisitor;
+
+        impl Visitor<'_> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a RESP string")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.as_bytes().to_vec()))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+impl From<ByteBuf> for Vec<u8> {
+    fn from(value: ByteBuf) -> Self {
+        value.0
+    }
+}
+
+/// The variant identifier given by `crate::Deserializer`: the prefix of the RESP kind.
+struct Kind(RespDataKind);
+
+impl<'de> serde::Deserialize<'de> for Kind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct KindVisitor;
+
+        impl Visitor<'_> for KindVisitor {
+            type Value = Kind;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a RESP prefix character")
+            }
+
+            fn visit_char<E>(self, v: char) -> Result<Kind, E>
+            where
+                E: serde::de::Error,
+            {
+                RespDataKind::try_from(v)
+                    .map(Kind)
+                    .map_err(|()| E::invalid_value(serde::de::Unexpected::Char(v), &self))
+            }
+        }
+
+        deserializer.deserialize_char(KindVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str, to_string};
+
+    fn round_trip(raw: &str, expected: Value) {
+        let value: Value = from_str(raw).unwrap();
+        assert_eq!(value, expected, "deserialize {raw:?}");
+        assert_eq!(to_string(&value).unwrap(), raw, "serialize {raw:?}");
+    }
+
+    #[test]
+    fn test_simple() {
+        round_trip("+OK\r\n", Value::SimpleString("OK".to_owned()));
+        round_trip("-ERR oops\r\n", Value::SimpleError("ERR oops".to_owned()));
+        round_trip(":-42\r\n", Value::Integer(-42));
+        round_trip("_\r\n", Value::Null);
+        round_trip("#f\r\n", Value::Boolean(false));
+        round_trip(",3.1\r\n", Value::Float(3.1));
+        round_trip(
+            "(-12345678901234567890123\r\n",
+            Value::BigNumber("-12345678901234567890123".parse().unwrap()),
+        );
+        assert!(from_str::<Value>("(12a\r\n").is_err());
+
+        // Numbers are written back in their shortest form
+        for (raw, written) in [
+            (":+5\r\n", ":5\r\n"),
+            (":-0\r\n", ":0\r\n"),
+            (",1.50\r\n", ",1.5\r\n"),
+            (",1e2\r\n", ",100\r\n"),
+            ("$-1\r\n", "_\r\n"),
+            ("*-1\r\n", "_\r\n"),
+        ] {
+            let value: Value = from_str(raw).unwrap();
+            assert_eq!(to_string(&value).unwrap(), written, "{raw:?}");
+        }
+        assert!(crate::parse_frame(b"(\r\n").is_err());
+    }
+
+    #[test]
+    fn test_bulk() {
+        round_trip("$5\r\nHello\r\n", Value::BulkString(b"Hello".to_vec()));
+        round_trip("$0\r\n\r\n", Value::BulkString(Vec::new()));
+    }
+
+    #[test]
+    fn test_non_utf8_bulk() {
+        let raw = b"$2\r\n\xff\x00\r\n";
+        let value: Value = crate::from_bytes(raw).unwrap();
+        assert_eq!(value, Value::BulkString(vec![0xff, 0x00]));
+        assert_eq!(crate::to_bytes(&value).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_bulk_error_and_verbatim() {
+        round_trip("!5\r\nError\r\n", Value::BulkError("Error".to_owned()));
+        round_trip(
+            "=15\r\ntxt:Some string\r\n",
+            Value::VerbatimString {
+                encoding: "txt".to_owned(),
+                text: "Some string".to_owned(),
+            },
+        );
+        assert!(from_str::<Value>("=8\r\nVerbatim\r\n").is_err());
+    }
+
+    #[test]
+    fn test_aggregates() {
+        round_trip(
+            "*3\r\n:1\r\n+two\r\n*1\r\n_\r\n",
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::SimpleString("two".to_owned()),
+                Value::Array(vec![Value::Null]),
+            ]),
+        );
+        round_trip(
+            "~2\r\n:1\r\n#t\r\n",
+            Value::Set(vec![Value::Integer(1), Value::Boolean(true)]),
+        );
+        round_trip(
+            ">2\r\n+message\r\n$2\r\nhi\r\n",
+            Value::Push(vec![
+                Value::SimpleString("message".to_owned()),
+                Value::BulkString(b"hi".to_vec()),
+            ]),
+        );
+        round_trip(
+            "%2\r\n:1\r\n+one\r\n*1\r\n:2\r\n~0\r\n",
+            Value::Map(vec![
+                (Value::Integer(1), Value::SimpleString("one".to_owned())),
+                (
+                    Value::Array(vec![Value::Integer(2)]),
+                    Value::Set(Vec::new()),
+                ),
+            ]),
+        );
+        round_trip(
+            "|1\r\n+ttl\r\n:3600\r\n",
+            Value::Attributes(vec![(
+                Value::SimpleString("ttl".to_owned()),
+                Value::Integer(3600),
+            )]),
+        );
+    }
+
+    #[test]
+    fn test_resp2_nulls() {
+        assert_eq!(from_str::<Value>("$-1\r\n").unwrap(), Value::Null);
+        assert_eq!(
+            from_str::<Value>("*2\r\n*-1\r\n:1\r\n").unwrap(),
+            Value::Array(vec![Value::Null, Value::Integer(1)])
+        );
+        for raw in ["$-1\r\n", "*-1\r\n"] {
+            let value: Value = from_str(raw).unwrap();
+            assert_eq!(to_string(&value).unwrap(), "_\r\n", "Written back as RESP3");
+        }
+    }
+
+    #[test]
+    fn test_accessors() {
+        assert!(Value::Null.is_null());
+        assert_eq!(Value::Boolean(true).as_bool(), Some(true));
+        assert_eq!(Value::Integer(-1).as_i64(), Some(-1));
+        assert_eq!(Value::Integer(-1).as_u64(), None);
+        assert_eq!(Value::Integer(2).as_f64(), Some(2.0));
+        let big = Value::BigNumber(u64::MAX.into());
+        assert_eq!(big.as_i64(), None);
+        assert_eq!(big.as_u64(), Some(u64::MAX));
+        assert_eq!(Value::SimpleError("ERR".to_owned()).as_str(), None);
+        assert_eq!(Value::SimpleError("ERR".to_owned()).as_error(), Some("ERR"));
+        assert_eq!(Value::BulkError("ERR".to_owned()).as_bytes(), None);
+        assert_eq!(Value::BulkError("ERR".to_owned()).as_error(), Some("ERR"));
+        assert_eq!(Value::SimpleString("OK".to_owned()).as_error(), None);
+        assert_eq!(Value::BulkString(vec![0xff]).as_str(), None);
+        assert_eq!(Value::BulkString(vec![0xff]).as_bytes(), Some(&[0xff][..]));
+        assert_eq!(
+            Value::Set(vec![Value::Null]).as_array().map(<[_]>::len),
+            Some(1)
+        );
+        assert_eq!(
+            Value::Attributes(Vec::new()).as_map().map(<[_]>::len),
+            Some(0)
+        );
+        assert_eq!(Value::Integer(1).as_str(), None);
+    }
+
+    #[test]
+    fn test_invalid_simple() {
+        let value = Value::SimpleString("a\r\nb".to_owned());
+        assert!(to_string(&value).is_err());
+    }
+}
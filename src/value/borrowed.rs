@@ -0,0 +1,217 @@
+use super::Value;
+use crate::big_number::check_digits;
+use crate::{BigNumber, RespDataKind};
+
+/// Any RESP value, borrowing its data from the input.
+///
+/// The borrowed counterpart of [`Value`], where strings, bulk payloads and big numbers are
+/// slices into the input buffer instead of copies. Aggregates still need a [`Vec`] to hold
+/// their elements, but no allocation is made per element.
+///
+/// ```
+/// # use rediserde::{ValueRef, from_bytes};
+/// let raw = b"*2\r\n$5\r\nHello\r\n+World\r\n";
+/// let value: ValueRef = from_bytes(raw).unwrap();
+/// let ValueRef::Array(items) = &value else { panic!("expected an array") };
+/// assert_eq!(items[0], ValueRef::BulkString(b"Hello"));
+/// assert_eq!(items[1], ValueRef::SimpleString("World"));
+/// // The payload points into the input buffer
+/// let ValueRef::BulkString(payload) = items[0] else { unreachable!() };
+/// assert!(raw.as_ptr_range().contains(&payload.as_ptr()));
+/// ```
+///
+/// Borrowing is only possible when the data appears as-is in the input, so deserializing
+/// from other formats may fail where deserializing a [`Value`] would not.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'de> {
+    /// See [`RespDataKind::SimpleString`]
+    SimpleString(&'de str),
+    /// See [`RespDataKind::SimpleError`]
+    SimpleError(&'de str),
+    /// See [`RespDataKind::Integer`]
+    Integer(i64),
+    /// See [`RespDataKind::BulkString`]
+    BulkString(&'de [u8]),
+    /// See [`RespDataKind::Array`]
+    Array(Vec<ValueRef<'de>>),
+    /// See [`RespDataKind::Null`]
+    Null,
+    /// See [`RespDataKind::Boolean`]
+    Boolean(bool),
+    /// See [`RespDataKind::Float`]
+    Float(f64),
+    /// See [`RespDataKind::BigNumber`], kept as the string of digits (with an optional sign),
+    /// which are checked when deserializing
+    BigNumber(&'de str),
+    /// See [`RespDataKind::BulkError`]
+    BulkError(&'de str),
+    /// See [`RespDataKind::VerbatimString`]
+    VerbatimString {
+        /// Exactly 3 characters, for example `txt` or `mkd`
+        encoding: &'de str,
+        text: &'de str,
+    },
+    /// See [`RespDataKind::Map`]
+    Map(Vec<(ValueRef<'de>, ValueRef<'de>)>),
+    /// See [`RespDataKind::Attributes`]
+    Attributes(Vec<(ValueRef<'de>, ValueRef<'de>)>),
+    /// See [`RespDataKind::Set`]
+    Set(Vec<ValueRef<'de>>),
+    /// See [`RespDataKind::Push`]
+    Push(Vec<ValueRef<'de>>),
+}
+
+impl ValueRef<'_> {
+    /// The RESP kind of this value.
+    #[must_use]
+    pub const fn kind(&self) -> RespDataKind {
+        match self {
+            Self::SimpleString(_) => RespDataKind::SimpleString,
+            Self::SimpleError(_) => RespDataKind::SimpleError,
+            Self::Integer(_) => RespDataKind::Integer,
+            Self::BulkString(_) => RespDataKind::BulkString,
+            Self::Array(_) => RespDataKind::Array,
+            Self::Null => RespDataKind::Null,
+            Self::Boolean(_) => RespDataKind::Boolean,
+            Self::Float(_) => RespDataKind::Float,
+            Self::BigNumber(_) => RespDataKind::BigNumber,
+            Self::BulkError(_) => RespDataKind::BulkError,
+            Self::VerbatimString { .. } => RespDataKind::VerbatimString,
+            Self::Map(_) => RespDataKind::Map,
+            Self::Attributes(_) => RespDataKind::Attributes,
+            Self::Set(_) => RespDataKind::Set,
+            Self::Push(_) => RespDataKind::Push,
+        }
+    }
+
+    /// Copies all borrowed data into an owned [`Value`].
+    ///
+    /// # Panics
+    ///
+    /// If a [`ValueRef::BigNumber`] that was built by hand is not a valid [`BigNumber`].
+    #[must_use]
+    pub fn into_owned(self) -> Value {
+        let items = |items: Vec<ValueRef>| items.into_iter().map(ValueRef::into_owned).collect();
+        let entries = |entries: Vec<(ValueRef, ValueRef)>| {
+            entries
+                .into_iter()
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect()
+        };
+        match self {
+            Self::SimpleString(s) => Value::SimpleString(s.to_string()),
+            Self::SimpleError(s) => Value::SimpleError(s.to_string()),
+            Self::Integer(i) => Value::Integer(i),
+            Self::BulkString(b) => Value::BulkString(b.to_vec()),
+            Self::Array(v) => Value::Array(items(v)),
+            Self::Null => Value::Null,
+            Self::Boolean(b) => Value::Boolean(b),
+            Self::Float(f) => Value::Float(f),
+            Self::BigNumber(n) => {
+                Value::BigNumber(BigNumber::new(n).expect("Checked when deserializing"))
+            }
+            Self::BulkError(s) => Value::BulkError(s.to_string()),
+            Self::VerbatimString { encoding, text } => Value::VerbatimString {
+                encoding: encoding.to_string(),
+                text: text.to_string(),
+            },
+            Self::Map(e) => Value::Map(entries(e)),
+            Self::Attributes(e) => Value::Attributes(entries(e)),
+            Self::Set(v) => Value::Set(items(v)),
+            Self::Push(v) => Value::Push(items(v)),
+        }
+    }
+}
+
+impl From<ValueRef<'_>> for Value {
+    fn from(value: ValueRef<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+super::impl_value_serde! {
+    ValueRef<'de>, ValueRefVisitor, "any borrowed RESP value",
+    bulk: &'de [u8],
+    verbatim: &'de str,
+    big: BorrowedDigits<'de>,
+    {
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+            Ok(ValueRef::BulkString(v.as_bytes()))
+        }
+
+        fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+            Ok(ValueRef::BulkString(v))
+        }
+    }
+}
+
+/// The data of a big number, checked the same way as [`BigNumber`] checks it.
+struct BorrowedDigits<'de>(&'de str);
+
+impl<'de> serde::Deserialize<'de> for BorrowedDigits<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let digits = <&'de str>::deserialize(deserializer)?;
+        check_digits(digits).map_err(serde::de::Error::custom)?;
+        Ok(Self(digits))
+    }
+}
+
+impl<'de> From<BorrowedDigits<'de>> for &'de str {
+    fn from(digits: BorrowedDigits<'de>) -> Self {
+        digits.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_bytes, from_str, to_string};
+
+    #[test]
+    fn test_round_trip() {
+        let raw = "*6\r\n+OK\r\n-ERR\r\n$3\r\nfoo\r\n(123456789012345678901\r\n=7\r\nmkd:# a\r\n%1\r\n:1\r\n~1\r\n!3\r\nbad\r\n";
+        let value: ValueRef = from_str(raw).unwrap();
+        assert_eq!(
+            value,
+            ValueRef::Array(vec![
+                ValueRef::SimpleString("OK"),
+                ValueRef::SimpleError("ERR"),
+                ValueRef::BulkString(b"foo"),
+                ValueRef::BigNumber("123456789012345678901"),
+                ValueRef::VerbatimString {
+                    encoding: "mkd",
+                    text: "# a"
+                },
+                ValueRef::Map(vec![(
+                    ValueRef::Integer(1),
+                    ValueRef::Set(vec![ValueRef::BulkError("bad")])
+                )]),
+            ])
+        );
+        assert_eq!(to_string(&value).unwrap(), raw);
+        let owned: Value = from_str(raw).unwrap();
+        assert_eq!(value.into_owned(), owned);
+        assert!(from_str::<ValueRef>("(abc\r\n").is_err());
+        assert!(from_str::<ValueRef>("*1\r\n(-\r\n").is_err());
+    }
+
+    #[test]
+    fn test_borrows_input() {
+        let raw = b"%1\r\n+key\r\n$3\r\n\xff\xfe\xfd\r\n".to_vec();
+        let value: ValueRef = from_bytes(&raw).unwrap();
+        let ValueRef::Map(entries) = value else {
+            panic!("Expected a map")
+        };
+        let (ValueRef::SimpleString(key), ValueRef::BulkString(payload)) = entries[0] else {
+            panic!("Expected a simple string key and a bulk string value")
+        };
+        assert_eq!(key, "key");
+        assert_eq!(payload, b"\xff\xfe\xfd");
+        let range = raw.as_ptr_range();
+        assert!(range.contains(&key.as_ptr()));
+        assert!(range.contains(&payload.as_ptr()));
+    }
+}
@@ -0,0 +1,637 @@
+use super::{TOKEN, Value, join_verbatim};
+use crate::frame::{format_float, parse_number, parse_wide_integer};
+use crate::{Error, Result};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeOwned, Expected, IntoDeserializer, Unexpected, Visitor};
+
+/// Converts a [`Value`] into any deserializable type, without going through RESP bytes.
+///
+/// Accepts the same values [`crate::from_bytes`] would accept for the type, so a reply
+/// can be parsed once and then converted in parts once its shape is known.
+///
+/// ```
+/// # use rediserde::{Value, from_value, parse_frame};
+/// # use serde::Deserialize;
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let raw = b"*2\r\n+person\r\n%2\r\n+name\r\n+Alice\r\n+age\r\n:30\r\n";
+/// let (Value::Array(mut items), _) = parse_frame(raw).unwrap() else { panic!() };
+/// if items[0] == Value::SimpleString("person".to_string()) {
+///     let person: Person = from_value(items.remove(1)).unwrap();
+///     assert_eq!(person, Person { name: "Alice".to_string(), age: 30 });
+/// }
+/// ```
+pub fn from_value<T>(value: Value) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+impl Value {
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Self::SimpleString(s) | Self::SimpleError(s) | Self::BulkError(s) => Unexpected::Str(s),
+            Self::VerbatimString { text, .. } => Unexpected::Str(text),
+            Self::BulkString(b) => Unexpected::Bytes(b),
+            Self::Integer(i) => Unexpected::Signed(*i),
+            Self::Float(f) => Unexpected::Float(*f),
+            Self::BigNumber(_) => Unexpected::Other("big number"),
+            Self::Boolean(b) => Unexpected::Bool(*b),
+            Self::Null => Unexpected::Unit,
+            Self::Array(_) | Self::Set(_) | Self::Push(_) => Unexpected::Seq,
+            Self::Map(_) | Self::Attributes(_) => Unexpected::Map,
+        }
+    }
+
+    fn invalid_type(&self, expected: &dyn Expected) -> Error {
+        serde::de::Error::invalid_type(self.unexpected(), expected)
+    }
+
+    /// Parses any number kind, the same way `crate::Deserializer` reads numbers.
+    fn into_number<N>(self, expected: &dyn Expected) -> Result<N>
+    where
+        N: std::str::FromStr,
+    {
+        match self {
+            Self::Integer(i) => parse_number(i.to_string().as_bytes()),
+            Self::Float(f) => parse_number(f.to_string().as_bytes()),
+            Self::BigNumber(n) => parse_number(n.as_str().as_bytes()),
+            other => Err(other.invalid_type(expected)),
+        }
+    }
+
+    /// Parses a 128-bit integer, the same way `crate::Deserializer` reads them.
+    fn into_wide_integer<N>(self, expected: &dyn Expected) -> Result<N>
+    where
+        N: std::str::FromStr<Err = std::num::ParseIntError>,
+    {
+        match self {
+            Self::Integer(i) => parse_wide_integer(i.to_string().as_bytes()),
+            Self::BigNumber(n) => parse_wide_integer(n.as_str().as_bytes()),
+            Self::SimpleString(s) => parse_wide_integer(s.as_bytes()),
+            Self::BulkString(b) => parse_wide_integer(&b),
+            other => Err(other.invalid_type(expected)),
+        }
+    }
+
+    /// The data of any string or number kind, which is not required to be UTF-8.
+    fn into_data(self, expected: &dyn Expected) -> Result<Vec<u8>> {
+        match self {
+            Self::SimpleString(s) | Self::SimpleError(s) | Self::BulkError(s) => Ok(s.into_bytes()),
+            Self::BigNumber(n) => Ok(n.into_string().into_bytes()),
+            Self::BulkString(b) => Ok(b),
+            Self::VerbatimString { encoding, text } => {
+                Ok(join_verbatim::<Error>(&encoding, &text)?.into_bytes())
+            }
+            Self::Integer(i) => Ok(i.to_string().into_bytes()),
+            Self::Float(f) => Ok(format_float(f).into_bytes()),
+            other => Err(other.invalid_type(expected)),
+        }
+    }
+}
+
+fn visit_seq<'de, V>(items: Vec<Value>, visitor: V) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let mut seq = SeqDeserializer::new(items.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn visit_map<'de, V>(entries: Vec<(Value, Value)>, visitor: V) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let mut map = MapDeserializer::new(entries.into_iter());
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+impl IntoDeserializer<'_, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::SimpleString(s) | Self::SimpleError(s) | Self::BulkError(s) => {
+                visitor.visit_string(s)
+            }
+            Self::VerbatimString { .. } => self.deserialize_string(visitor),
+            Self::BulkString(b) => match String::from_utf8(b) {
+                Ok(s) => visitor.visit_string(s),
+                Err(e) => visitor.visit_byte_buf(e.into_bytes()),
+            },
+            Self::Integer(i) => visitor.visit_i64(i),
+            Self::Float(f) => visitor.visit_f64(f),
+            Self::BigNumber(n) => {
+                let n = n.into_string();
+                if let Ok(i) = n.parse() {
+                    visitor.visit_i64(i)
+                } else if let Ok(u) = n.parse() {
+                    visitor.visit_u64(u)
+                } else if let Ok(i) = n.parse() {
+                    visitor.visit_i128(i)
+                } else if let Ok(u) = n.parse() {
+                    visitor.visit_u128(u)
+                } else {
+                    visitor.visit_string(n)
+                }
+            }
+            Self::Boolean(b) => visitor.visit_bool(b),
+            Self::Null => visitor.visit_unit(),
+            Self::Array(items) | Self::Set(items) | Self::Push(items) => visit_seq(items, visitor),
+            Self::Map(entries) | Self::Attributes(entries) => visit_map(entries, visitor),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::Boolean(b) => visitor.visit_bool(b),
+            other => Err(other.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_i8(v)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_i16(v)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_i32(v)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_i64(v)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_u8(v)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_u16(v)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_u32(v)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_u64(v)
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_wide_integer(&visitor)?;
+        visitor.visit_i128(v)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_wide_integer(&visitor)?;
+        visitor.visit_u128(v)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_f32(v)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.into_number(&visitor)?;
+        visitor.visit_f64(v)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let s = String::from_utf8(self.into_data(&visitor)?)?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(Error::DeserializeError(
+                "Expected a single character string".to_string(),
+            )),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let s = String::from_utf8(self.into_data(&visitor)?)?;
+        visitor.visit_string(s)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let b = self.into_data(&visitor)?;
+        visitor.visit_byte_buf(b)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::Null => visitor.visit_unit(),
+            other => Err(other.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    /// Answers the marker newtypes of [`crate::Value`] and [`crate::RawResp`] the same
+    /// way `crate::Deserializer` does, other newtypes are transparent.
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name == TOKEN {
+            return visitor.visit_enum(KindAccess(self));
+        }
+        if name == crate::raw::TOKEN {
+            return visitor.visit_byte_buf(crate::to_bytes(&self)?);
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::Array(items) | Self::Set(items) | Self::Push(items) => visit_seq(items, visitor),
+            other => Err(other.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::Map(entries) | Self::Attributes(entries) => visit_map(entries, visitor),
+            other => Err(other.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    /// Unit variants are strings, other variants are maps with a single pair.
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::SimpleString(_)
+            | Self::SimpleError(_)
+            | Self::BulkString(_)
+            | Self::BulkError(_)
+            | Self::VerbatimString { .. } => {
+                let s = String::from_utf8(self.into_data(&visitor)?)?;
+                visitor.visit_enum(s.into_deserializer())
+            }
+            Self::Map(mut entries) | Self::Attributes(mut entries) => {
+                match (entries.pop(), entries.is_empty()) {
+                    (Some((variant, value)), true) => {
+                        visitor.visit_enum(EnumDeserializer { variant, value })
+                    }
+                    _ => Err(Error::DeserializeError(
+                        "Expected a single key-value pair for enum variant".to_string(),
+                    )),
+                }
+            }
+            other => Err(other.invalid_type(&visitor)),
+        }
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+/// The single pair of a map, as an enum variant and its content.
+struct EnumDeserializer {
+    variant: Value,
+    value: Value,
+}
+
+impl<'de> serde::de::EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer)>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant)?;
+        Ok((variant, VariantDeserializer(self.value)))
+    }
+}
+
+struct VariantDeserializer(Value);
+
+impl<'de> serde::de::VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(Error::DeserializeError(
+            "Expected a unit variant, which must be a string".to_string(),
+        ))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.0)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        serde::Deserializer::deserialize_seq(self.0, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        serde::Deserializer::deserialize_map(self.0, visitor)
+    }
+}
+
+/// Exposes the kind of the value as the variant of an enum, same as `crate::de::KindAccess`.
+struct KindAccess(Value);
+
+impl<'de> serde::de::EnumAccess<'de> for KindAccess {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let prefix: serde::de::value::CharDeserializer<Error> =
+            self.0.kind().to_prefix_char().into_deserializer();
+        Ok((seed.deserialize(prefix)?, self))
+    }
+}
+
+impl<'de> serde::de::VariantAccess<'de> for KindAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.0 {
+            Value::Null => Ok(()),
+            other => Err(other.invalid_type(&"a null value")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.0)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        serde::Deserializer::deserialize_seq(self.0, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        serde::Deserializer::deserialize_map(self.0, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RawResp, from_bytes, parse_frame, to_value};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum E {
+        Unit,
+        Newtype(u32),
+        Tuple(u32, i8),
+        Struct { a: Option<bool> },
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Test {
+        int: u64,
+        float: f32,
+        text: String,
+        seq: Vec<char>,
+        map: BTreeMap<String, Vec<E>>,
+        unit: (),
+    }
+
+    #[test]
+    fn test_same_as_bytes() {
+        let raw = b"%6\r\n+int\r\n(1\r\n+float\r\n,1.5\r\n+text\r\n=7\r\ntxt:abc\r\n\
+            +seq\r\n~2\r\n+a\r\n$1\r\nb\r\n+map\r\n%1\r\n+x\r\n*4\r\n+Unit\r\n%1\r\n+Newtype\r\n:2\r\n\
+            %1\r\n+Tuple\r\n*2\r\n:3\r\n:-4\r\n%1\r\n+Struct\r\n%1\r\n+a\r\n_\r\n+unit\r\n_\r\n";
+        let expected: Test = from_bytes(raw).unwrap();
+        let (value, _) = parse_frame(raw).unwrap();
+        assert_eq!(from_value::<Test>(value).unwrap(), expected);
+        assert_eq!(expected.text, "txt:abc");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let test = Test {
+            int: 1,
+            float: 3.1,
+            text: "text".to_owned(),
+            seq: vec!['a', 'b'],
+            map: BTreeMap::from([("x".to_owned(), vec![E::Unit, E::Tuple(3, -4)])]),
+            unit: (),
+        };
+        assert_eq!(from_value::<Test>(to_value(&test).unwrap()).unwrap(), test);
+    }
+
+    #[test]
+    fn test_value() {
+        let raw = b"*6\r\n+OK\r\n-ERR\r\n(123456789012345678901234\r\n!3\r\nbad\r\n\
+            =7\r\ntxt:abc\r\n|1\r\n>1\r\n~1\r\n:1\r\n%1\r\n$2\r\n\xff\x00\r\n_\r\n";
+        let (value, _) = parse_frame(raw).unwrap();
+        assert_eq!(from_value::<Value>(value.clone()).unwrap(), value);
+        let raw_resp: Box<RawResp> = from_value(value).unwrap();
+        assert_eq!(raw_resp.as_bytes(), raw);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(from_value::<u8>(Value::Integer(256)).is_err());
+        assert!(from_value::<u8>(Value::SimpleString("1".to_owned())).is_err());
+        assert!(from_value::<String>(Value::BulkString(vec![0xff])).is_err());
+        assert!(from_value::<Vec<u8>>(Value::Map(Vec::new())).is_err());
+        assert!(from_value::<(u8,)>(Value::Array(vec![Value::Integer(1); 2])).is_err());
+        assert!(from_value::<E>(Value::Map(Vec::new())).is_err());
+        assert!(from_value::<i128>(Value::BigNumber(u128::MAX.into())).is_err());
+        assert!(from_value::<u128>(Value::Float(1.0)).is_err());
+    }
+
+    #[test]
+    fn test_128() {
+        let max = Value::BigNumber(u128::MAX.into());
+        assert_eq!(from_value::<u128>(max.clone()).unwrap(), u128::MAX);
+        assert_eq!(from_value::<i128>(Value::Integer(-1)).unwrap(), -1);
+        let text = Value::BulkString(i128::MIN.to_string().into_bytes());
+        assert_eq!(from_value::<i128>(text).unwrap(), i128::MIN);
+        assert_eq!(from_value::<Value>(max.clone()).unwrap(), max);
+    }
+}
@@ -0,0 +1,228 @@
+use super::Value;
+
+/// Types that can index into a [`Value`], see [`Value::get`].
+///
+/// - `usize` indexes arrays, sets and pushes by position, and maps by integer keys.
+/// - `str` and `String` index maps by simple, bulk or verbatim string keys, but not errors.
+/// - [`Value`] indexes maps by keys that are equal to it.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait ValueIndex: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value>;
+
+    #[doc(hidden)]
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value>;
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl Sealed for super::Value {}
+    impl<T> Sealed for &T where T: ?Sized + Sealed {}
+}
+
+/// Finds the value of the first entry with a matching key.
+fn find(value: &Value, is_key: impl Fn(&Value) -> bool) -> Option<&Value> {
+    let entries = value.as_map()?;
+    entries.iter().find(|(k, _)| is_key(k)).map(|(_, v)| v)
+}
+
+fn find_mut(value: &mut Value, is_key: impl Fn(&Value) -> bool) -> Option<&mut Value> {
+    match value {
+        Value::Map(entries) | Value::Attributes(entries) => {
+            entries.iter_mut().find(|(k, _)| is_key(k)).map(|(_, v)| v)
+        }
+        _ => None,
+    }
+}
+
+fn is_integer(key: &Value, index: usize) -> bool {
+    matches!(key, Value::Integer(i) if usize::try_from(*i) == Ok(index))
+}
+
+fn is_string(key: &Value, index: &str) -> bool {
+    key.as_str() == Some(index)
+}
+
+impl ValueIndex for usize {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        match value {
+            Value::Array(items) | Value::Set(items) | Value::Push(items) => items.get(*self),
+            _ => find(value, |key| is_integer(key, *self)),
+        }
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        match value {
+            Value::Array(items) | Value::Set(items) | Value::Push(items) => items.get_mut(*self),
+            _ => find_mut(value, |key| is_integer(key, *self)),
+        }
+    }
+}
+
+impl ValueIndex for str {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        find(value, |key| is_string(key, self))
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        find_mut(value, |key| is_string(key, self))
+    }
+}
+
+impl ValueIndex for String {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        self.as_str().index_into_mut(value)
+    }
+}
+
+impl ValueIndex for Value {
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        find(value, |key| key == self)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        find_mut(value, |key| key == self)
+    }
+}
+
+impl<T> ValueIndex for &T
+where
+    T: ?Sized + ValueIndex,
+{
+    fn index_into<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(value)
+    }
+
+    fn index_into_mut<'v>(&self, value: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(value)
+    }
+}
+
+impl Value {
+    /// Gets an element of an aggregate, or `None` if there is no such element.
+    ///
+    /// See [`ValueIndex`] for the types that can be used as an index.
+    ///
+    /// ```
+    /// # use rediserde::Value;
+    /// let value = Value::Map(vec![
+    ///     (Value::SimpleString("name".to_string()), Value::BulkString(b"Alice".to_vec())),
+    ///     (Value::Integer(1), Value::Array(vec![Value::Boolean(true)])),
+    /// ]);
+    /// assert_eq!(value.get("name"), Some(&Value::BulkString(b"Alice".to_vec())));
+    /// assert_eq!(value.get(1).and_then(|v| v.get(0)), Some(&Value::Boolean(true)));
+    /// assert_eq!(value.get("missing"), None);
+    /// ```
+    pub fn get<I>(&self, index: I) -> Option<&Self>
+    where
+        I: ValueIndex,
+    {
+        index.index_into(self)
+    }
+
+    /// Same as [`Value::get`], but returns a mutable reference.
+    pub fn get_mut<I>(&mut self, index: I) -> Option<&mut Self>
+    where
+        I: ValueIndex,
+    {
+        index.index_into_mut(self)
+    }
+
+    /// Gets a nested element by a `/` separated path, or `None` if there is no such element.
+    ///
+    /// Each part of the path is a map key, or a position in an array, set or push.
+    /// A part that is a number also matches integer map keys.
+    ///
+    /// ```
+    /// # use rediserde::{Value, parse_frame};
+    /// let raw = b"%1\r\n+results\r\n*2\r\n%1\r\n+score\r\n:1\r\n%1\r\n+score\r\n:2\r\n";
+    /// let (value, _) = parse_frame(raw).unwrap();
+    /// assert_eq!(value.get_path("results/1/score"), Some(&Value::Integer(2)));
+    /// assert_eq!(value.get_path("results/2/score"), None);
+    /// ```
+    #[must_use]
+    pub fn get_path(&self, path: &str) -> Option<&Self> {
+        path.split('/').try_fold(self, |value, part| {
+            part.index_into(value)
+                .or_else(|| part.parse::<usize>().ok()?.index_into(value))
+        })
+    }
+}
+
+static NULL: Value = Value::Null;
+
+/// Indexes into an aggregate, see [`Value::get`].
+///
+/// Returns [`Value::Null`] if there is no such element, so indexes can be chained.
+///
+/// ```
+/// # use rediserde::{Value, parse_frame};
+/// let (value, _) = parse_frame(b"%1\r\n+key\r\n*3\r\n:1\r\n:2\r\n:3\r\n").unwrap();
+/// assert_eq!(value["key"][2], Value::Integer(3));
+/// assert_eq!(value["missing"][2], Value::Null);
+/// ```
+impl<I> std::ops::Index<I> for Value
+where
+    I: ValueIndex,
+{
+    type Output = Self;
+
+    fn index(&self, index: I) -> &Self {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_frame;
+
+    const RAW: &[u8] = b"%4\r\n+name\r\n$5\r\nAlice\r\n$6\r\nscores\r\n*2\r\n:1\r\n,2.5\r\n\
+        :7\r\n~1\r\n#t\r\n*1\r\n:1\r\n=7\r\ntxt:abc\r\n";
+
+    #[test]
+    fn test_index() {
+        let (value, _) = parse_frame(RAW).unwrap();
+        assert_eq!(value["name"].as_str(), Some("Alice"));
+        assert_eq!(value["scores"][1], Value::Float(2.5));
+        assert_eq!(value[7][0], Value::Boolean(true));
+        assert_eq!(
+            value[&Value::Array(vec![Value::Integer(1)])].as_str(),
+            Some("abc")
+        );
+        assert_eq!(value["scores".to_string()][0], Value::Integer(1));
+        assert_eq!(value["scores"][2], Value::Null);
+        assert_eq!(value["name"]["nested"], Value::Null);
+        assert_eq!(value[0], Value::Null);
+        let errors = Value::Map(vec![(
+            Value::SimpleError("ERR".to_string()),
+            Value::Integer(1),
+        )]);
+        assert_eq!(errors["ERR"], Value::Null);
+    }
+
+    #[test]
+    fn test_get_path() {
+        let (value, _) = parse_frame(RAW).unwrap();
+        assert_eq!(value.get_path("scores/0"), Some(&Value::Integer(1)));
+        assert_eq!(value.get_path("7/0"), Some(&Value::Boolean(true)));
+        assert_eq!(value.get_path("scores/x"), None);
+        assert_eq!(value.get_path("scores/0/x"), None);
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let (mut value, _) = parse_frame(RAW).unwrap();
+        *value.get_mut("scores").unwrap().get_mut(0).unwrap() = Value::Null;
+        assert_eq!(value["scores"][0], Value::Null);
+        assert!(value.get_mut("missing").is_none());
+    }
+}
@@ -0,0 +1,493 @@
+use super::{Value, split_verbatim};
+use crate::{BigNumber, Error, RespDataKind, Result};
+use serde::Serialize;
+
+/// Converts any serializable type into a [`Value`], without going through RESP bytes.
+///
+/// Every part of the data gets the same RESP kind [`crate::to_bytes`] would give it.
+///
+/// ```
+/// # use rediserde::{Value, to_value};
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let person = Person { name: "Alice".to_string(), age: 30 };
+/// assert_eq!(
+///     to_value(&person).unwrap(),
+///     Value::Map(vec![
+///         (Value::BulkString(b"name".to_vec()), Value::BulkString(b"Alice".to_vec())),
+///         (Value::BulkString(b"age".to_vec()), Value::Integer(30)),
+///     ])
+/// );
+/// ```
+pub fn to_value<T>(value: &T) -> Result<Value>
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(Serializer::default())
+}
+
+/// Builds a [`Value`] with the same structure `crate::Serializer` writes.
+#[derive(Default)]
+struct Serializer {
+    /// The kind requested by a marker newtype struct, see `RespDataKind::to_token`.
+    kind: Option<RespDataKind>,
+    /// Set by [`crate::RawResp`], the bytes are a complete RESP value.
+    raw: bool,
+}
+
+fn utf8(v: &[u8]) -> Result<String> {
+    Ok(str::from_utf8(v)?.to_string())
+}
+
+/// Enum variants with data are represented as a map with a single pair.
+fn variant(name: &str, value: Value) -> Value {
+    Value::Map(vec![(Value::BulkString(name.as_bytes().to_vec()), value)])
+}
+
+impl serde::Serializer for Serializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeVec;
+    type SerializeMap = SerializeEntries;
+    type SerializeStruct = SerializeEntries;
+    type SerializeStructVariant = SerializeEntries;
+
+    fn serialize_bool(self, v: bool) -> Result<Value> {
+        Ok(Value::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value> {
+        Ok(Value::Integer(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value> {
+        self.serialize_i64(v.into())
+    }
+
+    /// Same as `crate::Serializer`, a u64 is always a `BigNumber`.
+    fn serialize_u64(self, v: u64) -> Result<Value> {
+        Ok(Value::BigNumber(v.into()))
+    }
+
+    /// Same as `crate::Serializer`, 128-bit integers are always a `BigNumber`.
+    fn serialize_i128(self, v: i128) -> Result<Value> {
+        Ok(Value::BigNumber(v.into()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value> {
+        Ok(Value::BigNumber(v.into()))
+    }
+
+    /// Goes through the string representation, so the value is the same one
+    /// that is read back from RESP (e.g. `3.1_f32` becomes `3.1_f64`).
+    fn serialize_f32(self, v: f32) -> Result<Value> {
+        let v = crate::frame::format_float(v)
+            .parse()
+            .expect("A formatted f32 is a valid f64");
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value> {
+        self.serialize_str(v.to_string().as_str())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    /// A bulk string, unless marked otherwise by [`crate::Value`].
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value> {
+        if self.raw {
+            let (value, _) = crate::parse_frame(v)?;
+            return Ok(value);
+        }
+        let value = match self.kind {
+            Some(RespDataKind::SimpleString) => Value::SimpleString(utf8(v)?),
+            Some(RespDataKind::SimpleError) => Value::SimpleError(utf8(v)?),
+            Some(RespDataKind::BigNumber) => Value::BigNumber(BigNumber::new(utf8(v)?)?),
+            Some(RespDataKind::BulkError) => Value::BulkError(utf8(v)?),
+            Some(RespDataKind::VerbatimString) => {
+                let (encoding, text) = split_verbatim::<Error>(str::from_utf8(v)?)?;
+                Value::VerbatimString {
+                    encoding: encoding.to_string(),
+                    text: text.to_string(),
+                }
+            }
+            _ => Value::BulkString(v.to_vec()),
+        };
+        Ok(value)
+    }
+
+    fn serialize_none(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(Self {
+            kind: RespDataKind::from_token(name),
+            raw: name == crate::raw::TOKEN,
+        })
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        value: &T,
+    ) -> Result<Value>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(variant(variant_name, to_value(value)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec> {
+        let kind = match self.kind {
+            Some(kind @ (RespDataKind::Set | RespDataKind::Push)) => kind,
+            _ => RespDataKind::Array,
+        };
+        Ok(SerializeVec {
+            kind,
+            variant: None,
+            items: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SerializeVec> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec> {
+        Ok(SerializeVec {
+            kind: RespDataKind::Array,
+            variant: Some(variant),
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeEntries> {
+        let kind = match self.kind {
+            Some(RespDataKind::Attributes) => RespDataKind::Attributes,
+            _ => RespDataKind::Map,
+        };
+        Ok(SerializeEntries {
+            kind,
+            variant: None,
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeEntries> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeEntries> {
+        Ok(SerializeEntries {
+            kind: RespDataKind::Map,
+            variant: Some(variant),
+            entries: Vec::with_capacity(len),
+            key: None,
+        })
+    }
+}
+
+/// Collects the items of any sequence, and tuple variants.
+struct SerializeVec {
+    kind: RespDataKind,
+    variant: Option<&'static str>,
+    items: Vec<Value>,
+}
+
+impl serde::ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.items.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        let value = match self.kind {
+            RespDataKind::Set => Value::Set(self.items),
+            RespDataKind::Push => Value::Push(self.items),
+            _ => Value::Array(self.items),
+        };
+        Ok(match self.variant {
+            Some(name) => variant(name, value),
+            None => value,
+        })
+    }
+}
+
+impl serde::ser::SerializeTuple for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleVariant for SerializeVec {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+/// Collects the entries of any map or struct, and struct variants.
+struct SerializeEntries {
+    kind: RespDataKind,
+    variant: Option<&'static str>,
+    entries: Vec<(Value, Value)>,
+    /// The last key, waiting for its value
+    key: Option<Value>,
+}
+
+impl serde::ser::SerializeMap for SerializeEntries {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(to_value(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::SerializeError("A map value must follow its key".to_string()))?;
+        self.entries.push((key, to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value> {
+        let value = match self.kind {
+            RespDataKind::Attributes => Value::Attributes(self.entries),
+            _ => Value::Map(self.entries),
+        };
+        Ok(match self.variant {
+            Some(name) => variant(name, value),
+            None => value,
+        })
+    }
+}
+
+impl serde::ser::SerializeStruct for SerializeEntries {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeMap::serialize_entry(self, key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        serde::ser::SerializeMap::end(self)
+    }
+}
+
+impl serde::ser::SerializeStructVariant for SerializeEntries {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        serde::ser::SerializeMap::serialize_entry(self, key, value)
+    }
+
+    fn end(self) -> Result<Value> {
+        serde::ser::SerializeMap::end(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RawResp, parse_frame, to_bytes};
+    use std::collections::BTreeMap;
+
+    /// Same as serializing to bytes and parsing them back.
+    fn assert_same<T: Serialize>(value: &T) {
+        let (expected, _) = parse_frame(&to_bytes(value).unwrap()).unwrap();
+        assert_eq!(to_value(value).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_same_as_bytes() {
+        #[derive(Serialize)]
+        enum E {
+            Unit,
+            Newtype(u32),
+            Tuple(u32, i8),
+            Struct { a: Option<bool> },
+        }
+
+        #[derive(Serialize)]
+        struct Test {
+            int: u64,
+            float: f32,
+            seq: Vec<String>,
+            map: BTreeMap<char, Vec<E>>,
+            unit: (),
+        }
+
+        let test = Test {
+            int: 1,
+            float: 3.1,
+            seq: vec!["a".to_owned(), "b".to_owned()],
+            map: BTreeMap::from([
+                ('x', vec![E::Unit, E::Newtype(2)]),
+                ('y', vec![E::Tuple(3, -4), E::Struct { a: Some(true) }]),
+            ]),
+            unit: (),
+        };
+        assert_same(&test);
+        assert_same(&E::Struct { a: None });
+        assert_same(&(i128::MIN, u128::MAX, 1_u128));
+    }
+
+    #[test]
+    fn test_value() {
+        let raw = b"*6\r\n+OK\r\n-ERR\r\n(123456789012345678901234\r\n!3\r\nbad\r\n\
+            =7\r\ntxt:abc\r\n|1\r\n>1\r\n~1\r\n:1\r\n%1\r\n$2\r\n\xff\x00\r\n_\r\n";
+        let (value, _) = parse_frame(raw).unwrap();
+        assert_eq!(to_value(&value).unwrap(), value);
+    }
+
+    #[test]
+    fn test_raw() {
+        let raw = RawResp::from_bytes(b"~2\r\n+a\r\n#f\r\n").unwrap();
+        assert_eq!(
+            to_value(raw).unwrap(),
+            Value::Set(vec![
+                Value::SimpleString("a".to_owned()),
+                Value::Boolean(false)
+            ])
+        );
+    }
+}
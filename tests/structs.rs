@@ -85,3 +85,24 @@ fn simple() {
     assert_eq!(deserialized, person);
     assert_eq!(deserialized_raw, person);
 }
+
+#[test]
+fn macro_encoded() {
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    let encoded = rediserde::resp! { map { simple "name" => simple "Alice", simple "age" => 30 } };
+    let person: Person = rediserde::from_bytes(&encoded).unwrap();
+    assert_eq!(
+        person,
+        Person {
+            name: "Alice".to_string(),
+            age: 30,
+        }
+    );
+}